@@ -1,10 +1,10 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::{Arc, OnceLock, Weak}};
 
 use itertools::Itertools;
 
-use crate::{data::{Either, EitherParser, Output, Parser, QuadrupleParser, TripleParser, TupleParser}, system::{Lazy, Thunk}};
+use crate::{data::{fresh_parser_id, Either, EitherParser, Expected, MemoKey, Output, ParseError, Parser, Spanned, State, QuadrupleParser, Text, TripleParser, TupleParser, Unit}, system::{Lazy, Thunk}};
 
-impl<T> Parser<T> where T: Clone + 'static {
+impl<T> Parser<T> where T: Clone + Send + Sync + 'static {
     pub fn pure(value: T) -> Self {
         Self::init(move |s| s.ok(value.clone()))
     }
@@ -16,62 +16,122 @@ impl<T> Parser<T> {
     }
 }
 
+impl Parser<Unit> {
+    /// Succeed, consuming nothing, only once all input has been consumed.
+    pub fn eof() -> Self {
+        Self::init(|state| {
+            if state.examine_end() {
+                return state.ok(Unit)
+            }
+            state.fail_expecting(Expected::EndOfInput)
+        })
+    }
+}
+
 impl<A: Debug> Parser<A> where A: Clone + 'static {
-    pub fn and_then<B: Debug>(
+    pub fn and_then<B>(
         self,
-        right: impl Fn(A) -> Parser<B> + 'static
-    ) -> Parser<B> where B: Clone + 'static {
+        right: impl Fn(A) -> Parser<B> + Send + Sync + 'static
+    ) -> Parser<B> where B: Debug + Clone + 'static {
         let left = self.binder.clone();
         Parser::<B>::init(move |s1| {
             match left(s1) {
                 Output::Ok { value: t, state: s2 } => (right(t).binder)(s2),
-                Output::Fail { state } => state.fail()
+                Output::Fail { state, error } => state.fail_with(error),
+                Output::Error { state, error } => state.cut(error),
             }
         })
     }
-    pub fn map<B: Debug>(
+    pub fn map<B>(
         self,
-        right: impl Fn(A) -> B + 'static
-    ) -> Parser<B> where B: Clone + 'static {
-        self.and_then(move |t| {
-            Parser::<B>::pure(right(t))
+        right: impl Fn(A) -> B + Send + Sync + 'static
+    ) -> Parser<B> where B: Debug + Clone + 'static {
+        Parser::<B>::init(move |state| {
+            match (self.binder)(state) {
+                Output::Ok { value, state } => state.ok(right(value)),
+                Output::Fail { state, error } => state.fail_with(error),
+                Output::Error { state, error } => state.cut(error),
+            }
+        })
+    }
+    /// Like `map`, but `right` also gets the input this parser started at,
+    /// e.g. to anchor a `Text::from_chars` that may be empty.
+    pub fn map_with_start<B>(
+        self,
+        right: impl Fn(&Text, A) -> B + Send + Sync + 'static
+    ) -> Parser<B> where B: Debug + Clone + 'static {
+        Parser::<B>::init(move |state| {
+            let start = state.text.clone();
+            match (self.binder)(state) {
+                Output::Ok { value, state } => state.ok(right(&start, value)),
+                Output::Fail { state, error } => state.fail_with(error),
+                Output::Error { state, error } => state.cut(error),
+            }
         })
     }
     pub fn optional(self) -> Parser<Option<A>> {
         Parser::<Option<A>>::init(move |state| {
             match (self.binder)(state) {
                 Output::Ok { value, state } => state.ok(Some(value)),
-                Output::Fail { state } => state.ok(None),
+                Output::Fail { state, .. } => state.ok(None),
+                Output::Error { state, error } => state.cut(error),
             }
         })
     }
     pub fn options<P: Lazy<Item = Self>>(options: Vec<P>) -> Self {    
         Self::init(move |state| {
+            let mut failure: Option<ParseError> = None;
             for op in options.clone() {
                 match (op.evaluate().binder)(state.clone()) {
                     Output::Ok { value, state } => return state.ok(value),
-                    Output::Fail { .. } => continue,
+                    Output::Error { state, error } => return state.cut(error),
+                    Output::Fail { error, .. } => {
+                        failure = Some(match failure {
+                            Some(failure) => failure.merge(error),
+                            None => error,
+                        });
+                    }
                 }
             }
-            return state.fail()
+            match failure {
+                Some(error) => state.fail_with(error),
+                None => state.fail(),
+            }
         })
     }
     pub fn options_(options: Vec<Self>) -> Self {    
         Self::init(move |state| {
+            let mut failure: Option<ParseError> = None;
             for op in options.clone() {
                 match (op.binder)(state.clone()) {
                     Output::Ok { value, state } => return state.ok(value),
-                    Output::Fail { .. } => continue,
+                    Output::Error { state, error } => return state.cut(error),
+                    Output::Fail { error, .. } => {
+                        failure = Some(match failure {
+                            Some(failure) => failure.merge(error),
+                            None => error,
+                        });
+                    }
                 }
             }
-            return state.fail()
+            match failure {
+                Some(error) => state.fail_with(error),
+                None => state.fail(),
+            }
         })
     }
     pub fn or(self, next: Self) -> Self {
         Self::init(move |state| {
             match (self.binder)(state) {
                 Output::Ok { value, state } => state.ok(value),
-                Output::Fail { state } => (next.binder)(state),
+                Output::Fail { state, error: left } => {
+                    match (next.binder)(state) {
+                        Output::Ok { value, state } => state.ok(value),
+                        Output::Fail { state, error: right } => state.fail_with(left.merge(right)),
+                        Output::Error { state, error } => state.cut(error),
+                    }
+                },
+                Output::Error { state, error } => state.cut(error),
             }
         })
     }
@@ -79,26 +139,305 @@ impl<A: Debug> Parser<A> where A: Clone + 'static {
         EitherParser::<A, B>::init(move |state| {
             match (self.binder)(state) {
                 Output::Ok { value, state } => state.ok(Either::Left(value)),
-                Output::Fail { state } => {
+                Output::Fail { state, error: left } => {
                     match (other.clone().evaluate().binder)(state) {
                         Output::Ok { value, state } => state.ok(Either::Right(value)),
-                        Output::Fail { state } => state.fail(),
+                        Output::Fail { state, error: right } => state.fail_with(left.merge(right)),
+                        Output::Error { state, error } => state.cut(error),
                     }
                 },
+                Output::Error { state, error } => state.cut(error),
             }
         })
     }
-    pub fn between_both_ends<B: Debug>(self, end: Parser<B>) -> TripleParser<B, A, B> where B: Clone + 'static {
+    /// Report failures that occur before any progress as expecting `name`.
+    ///
+    /// Failures past the starting position are left as-is, since they say more
+    /// about what went wrong than the label does.
+    pub fn label(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self::init(move |state| {
+            let start = state.offset();
+            state.tracer.clone().run(&name, state, |state| {
+                match (self.binder)(state) {
+                    Output::Fail { state, mut error } if error.position.byte_offset <= start => {
+                        error.expected.clear();
+                        error.expected.insert(Expected::Label(name.clone()));
+                        state.fail_with(error)
+                    }
+                    output => output,
+                }
+            })
+        })
+    }
+    /// Push `name` onto the context stack for the duration of this parser.
+    pub fn context(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self::init(move |state| {
+            let outer = state.context.clone();
+            state.tracer.clone().run(&name, state, |state| {
+                match (self.binder)(state.push_context(name.clone())) {
+                    Output::Ok { value, state } => state.set_context(outer).ok(value),
+                    Output::Fail { state, error } => state.set_context(outer).fail_with(error),
+                    Output::Error { state, error } => state.set_context(outer).cut(error),
+                }
+            })
+        })
+    }
+    /// Record entering and leaving this parser with the evaluation's `Tracer`.
+    ///
+    /// `label` and `context` are traced the same way under their names.
+    ///
+    /// Costs nothing beyond a check when tracing is disabled, which is the default.
+    pub fn trace(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self::init(move |state| {
+            state.tracer.clone().run(&name, state, |state| (self.binder)(state))
+        })
+    }
+    /// Record where in the source this parser's value came from.
+    pub fn with_span(self) -> Parser<Spanned<A>> {
+        Parser::<Spanned<A>>::init(move |state| {
+            let start = state.position();
+            match (self.binder)(state) {
+                Output::Ok { value, state } => {
+                    let end = state.position();
+                    state.ok(Spanned { value, start, end })
+                }
+                Output::Fail { state, error } => state.fail_with(error),
+                Output::Error { state, error } => state.cut(error),
+            }
+        })
+    }
+    /// Cache this parser's result per input offset for the rest of the evaluation.
+    ///
+    /// Turns repeated attempts at the same position (common when alternatives
+    /// share a prefix) into table lookups. Results are assumed to depend only on
+    /// the input, so don't memoize parsers that read or write user state or
+    /// use `Parser::recover_with`.
+    pub fn memoize(self) -> Self {
+        let id = fresh_parser_id();
+        Self::init(move |state| {
+            let key = MemoKey { parser: id, offset: state.offset() };
+            if let Some(output) = state.memo.get::<A>(key) {
+                return output.replay(&state)
+            }
+            let output = (self.binder)(state.clone());
+            state.memo.insert(key, output.clone().detach(&state));
+            output
+        })
+    }
+    /// Build a rule that may refer to itself in leftmost position, e.g.
+    /// `expr := expr '+' term | term`.
+    ///
+    /// `rule` receives a parser standing in for the rule itself. At each offset
+    /// the leftmost self-reference first fails (the seed), then is re-run with
+    /// the last successful result for as long as that makes the match longer.
+    /// Self-references elsewhere, as in `'(' expr ')'`, run the whole rule.
+    pub fn left_recursive(rule: impl FnOnce(Self) -> Self) -> Self {
+        let id = fresh_parser_id();
+        // - THE RULE REFERS TO ITSELF, SO IT ONLY HOLDS A WEAK HANDLE -
+        let whole = Arc::new(OnceLock::<Weak<dyn Fn(State) -> Output<A> + Send + Sync>>::new());
+        let recurse = Self::init({
+            let whole = whole.clone();
+            move |state| {
+                let key = MemoKey { parser: id, offset: state.offset() };
+                if let Some(output) = state.memo.get::<A>(key) {
+                    return output.replay(&state)
+                }
+                let whole = whole
+                    .get()
+                    .and_then(Weak::upgrade)
+                    .expect("a left-recursive rule outlived its parser");
+                whole(state)
+            }
+        });
+        let body = rule(recurse);
+        let parser = Self::init(move |state| {
+            let key = MemoKey { parser: id, offset: state.offset() };
+            if let Some(output) = state.memo.get::<A>(key) {
+                return output.replay(&state)
+            }
+            // - SEED -
+            let mut best: Output<A> = state.clone().fail();
+            state.memo.pin(key, best.clone().detach(&state));
+            // - GROW -
+            loop {
+                let output = (body.binder)(state.clone());
+                let grew = match (&output, &best) {
+                    (Output::Ok { state: next, .. }, Output::Ok { state: previous, .. }) => {
+                        next.offset() > previous.offset()
+                    }
+                    (Output::Ok { .. }, _) => true,
+                    (Output::Error { .. }, _) => {
+                        best = output;
+                        break
+                    }
+                    (Output::Fail { .. }, Output::Ok { .. }) => false,
+                    (Output::Fail { .. }, _) => {
+                        best = output;
+                        break
+                    }
+                };
+                if !grew {
+                    break
+                }
+                best = output;
+                state.memo.pin(key, best.clone().detach(&state));
+            }
+            state.memo.unpin(key);
+            let best = best.detach(&state);
+            state.memo.insert(key, best.clone());
+            best.replay(&state)
+        });
+        let _ = whole.set(Arc::downgrade(&parser.binder));
+        parser
+    }
+    /// Turn any failure of this parser into a committed error.
+    ///
+    /// Use it once a branch has consumed enough input to be sure it is the
+    /// right one: `or`, `options`, `optional` and friends then stop trying
+    /// alternatives and report this failure instead.
+    pub fn cut(self) -> Self {
+        Self::init(move |state| {
+            match (self.binder)(state) {
+                Output::Fail { state, error } => state.cut(error),
+                output => output,
+            }
+        })
+    }
+    /// Alias for [`Parser::cut`].
+    pub fn commit(self) -> Self {
+        self.cut()
+    }
+    pub fn between_both_ends<B>(self, end: Parser<B>) -> TripleParser<B, A, B> where B: Debug + Clone + 'static {
         end.clone().and2(Thunk::wrap(move || self.clone()), Thunk::wrap(move || end.clone()))
     }
-    pub fn between<Left: Debug, Right: Debug>(
+    pub fn between<Left, Right>(
         self,
         left: Parser<Left>,
         right: Parser<Right>,
-    ) -> TripleParser<Left, A, Right> where Left: Clone + 'static, Right: Clone + 'static {
+    ) -> TripleParser<Left, A, Right> where Left: Debug + Clone + 'static, Right: Debug + Clone + 'static {
         left.and2(
             Thunk::wrap(move || self.clone()),
             Thunk::wrap(move || right.clone()),
         )
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::data::{CharParser, EvaluateSettings, Expected, Parser, TextParser, Unit};
+
+    /// `expr := expr '+' digit | '(' expr ')' | digit`
+    fn sum() -> Parser<u32> {
+        Parser::<u32>::left_recursive(|expr| {
+            let digit = CharParser::char_if(|x| x.is_ascii_digit()).map(|x| x.value.to_digit(10).unwrap());
+            let plus = expr.clone()
+                .and_(CharParser::char('+'))
+                .and_(digit.clone())
+                .map(|((left, _), right)| left + right);
+            let group = CharParser::char('(')
+                .and_(expr)
+                .and_(CharParser::char(')'))
+                .map(|((_, value), _)| value);
+            plus.or(group).or(digit)
+        })
+    }
+
+    #[test]
+    fn left_recursive_grows_leftmost_references() {
+        assert_eq!(sum().parse("1+2+3").unwrap(), 6);
+    }
+
+    #[test]
+    fn left_recursive_runs_nested_references() {
+        assert_eq!(sum().parse("(1+2)").unwrap(), 3);
+        assert_eq!(sum().parse("((1+2)+3)+4").unwrap(), 10);
+    }
+
+    #[test]
+    fn left_recursive_survives_a_full_memo_table() {
+        let settings = EvaluateSettings::default().memo_capacity(1);
+        let (result, state) = Parser::evaluate_complete_with("((1+2)+3)+4+5", sum(), settings);
+        assert_eq!(result.unwrap(), 15);
+        assert!(state.at_end());
+    }
+
+    #[test]
+    fn memoize_replays_the_result() {
+        let letter = CharParser::char_if(char::is_alphabetic).memoize();
+        let parser = letter.clone().and_(CharParser::char('!')).or(letter.clone().and_(CharParser::char('?')));
+        let (result, state) = Parser::evaluate("a?", parser);
+        assert_eq!(result.unwrap().1.value, '?');
+        assert_eq!(state.memo.len(), 1);
+    }
+
+    #[test]
+    fn memoize_keeps_the_callers_user_state() {
+        let letter = CharParser::char('a').memoize();
+        let first = Parser::<Unit>::put_state(1u32)
+            .and_(letter.clone())
+            .and_(CharParser::char('x'))
+            .map(|_| 0u32);
+        let second = Parser::<Unit>::put_state(2u32)
+            .and_(letter)
+            .and_(Parser::<u32>::get_state())
+            .map(|(_, value)| value);
+        assert_eq!(first.or(second).parse("a").unwrap(), 2);
+    }
+
+    #[test]
+    fn memoize_reports_the_callers_context() {
+        let letter = CharParser::char('a').context("letter").memoize();
+        let parser = letter.clone().context("first").optional().and_(letter.context("second"));
+        let error = parser.parse("x").unwrap_err();
+        assert_eq!(error.context, ["letter", "second"]);
+    }
+
+    #[test]
+    fn label_names_failures_before_any_progress() {
+        let parser = CharParser::char_if(|x| x.is_ascii_digit()).some().label("number");
+        let error = parser.parse("x").unwrap_err();
+        assert_eq!(error.expected.into_iter().collect::<Vec<_>>(), [Expected::Label(String::from("number"))]);
+        let error = TextParser::token("ab").and_(CharParser::char('c')).label("abc").parse("abx").unwrap_err();
+        assert!(error.expected.contains(&Expected::Char('c')));
+    }
+
+    #[test]
+    fn context_is_reported_innermost_first() {
+        let inner = CharParser::char('b').context("inner");
+        let parser = CharParser::char('a').and_(inner).context("outer");
+        let error = parser.parse("ax").unwrap_err();
+        assert_eq!(error.context, ["inner", "outer"]);
+        assert_eq!(error.to_string(), "expected 'b', found 'x' (in inner > in outer)");
+    }
+
+    #[test]
+    fn cut_stops_trying_alternatives() {
+        let committed = CharParser::char('a').and_(CharParser::char('b').cut()).map(|_| 1);
+        let fallback = CharParser::char('a').and_(CharParser::char('c')).map(|_| 2);
+        assert!(committed.clone().or(fallback.clone()).parse("ac").is_err());
+        let uncommitted = CharParser::char('a').and_(CharParser::char('b')).map(|_| 1);
+        assert_eq!(uncommitted.or(fallback).parse("ac").unwrap(), 2);
+    }
+
+    #[test]
+    fn with_span_records_where_the_value_came_from() {
+        let word = CharParser::char_if(char::is_alphabetic).some().with_span();
+        let parser = CharParser::char('\n').and_(word).map(|(_, word)| word);
+        let spanned = parser.parse("\nab").unwrap();
+        assert_eq!(spanned.span().byte_range(), 1..3);
+        assert_eq!(spanned.span().line_range(), 1..2);
+        assert_eq!(spanned.span().column_range(), 0..2);
+        assert_eq!(spanned.slice("\nab"), Some("ab"));
+    }
+
+    #[test]
+    fn eof_only_matches_at_the_end() {
+        let parser = CharParser::char('a').and_(Parser::eof());
+        assert!(parser.parse("a").is_ok());
+        let error = parser.parse("ab").unwrap_err();
+        assert!(error.expected.contains(&Expected::EndOfInput));
+        assert_eq!(error.position.byte_offset, 1);
+    }
+}
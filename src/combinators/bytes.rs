@@ -0,0 +1,151 @@
+use std::fmt::Debug;
+
+use crate::data::{ByteParser, Bytes, BytesParser, Expected, Parser, State};
+
+impl ByteParser {
+    pub fn next_byte() -> Self {
+        Self::init(|state| {
+            match state.bytes.as_ref().and_then(Bytes::uncons) {
+                Some((head, rest)) => state.set_bytes(rest).ok(head),
+                None => state.fail(),
+            }
+        })
+    }
+    pub fn byte(value: u8) -> Self {
+        Self::init(move |state| {
+            match state.bytes.as_ref().and_then(Bytes::uncons) {
+                Some((head, rest)) if head == value => state.set_bytes(rest).ok(head),
+                _ => state.fail_expecting(Expected::Byte(value)),
+            }
+        })
+    }
+    pub fn byte_if(predicate: impl Fn(u8) -> bool + Send + Sync + 'static) -> Self {
+        Self::init(move |state| {
+            match state.bytes.as_ref().and_then(Bytes::uncons) {
+                Some((head, rest)) if predicate(head) => state.set_bytes(rest).ok(head),
+                _ => state.fail(),
+            }
+        })
+    }
+}
+
+impl BytesParser {
+    /// Exactly `count` bytes.
+    pub fn bytes(count: usize) -> Self {
+        Self::init(move |state| {
+            match take(&state, count) {
+                Some((leading, rest)) => state.set_bytes(rest).ok(leading),
+                None => state.fail_expecting(Expected::Label(format!("{count} bytes"))),
+            }
+        })
+    }
+    /// The bytes of `value`, such as a magic number.
+    pub fn tag(value: impl Into<Vec<u8>>) -> Self {
+        let value = value.into();
+        Self::init(move |state| {
+            match take(&state, value.len()) {
+                Some((leading, rest)) if leading.as_slice() == value => state.set_bytes(rest).ok(leading),
+                _ => state.fail_expecting(Expected::Bytes(value.clone())),
+            }
+        })
+    }
+    /// The longest run of bytes matching `predicate`; may be empty.
+    pub fn take_while_byte(predicate: impl Fn(u8) -> bool + Send + Sync + 'static) -> Self {
+        Self::init(move |state| {
+            let count = state.bytes
+                .as_ref()
+                .map_or(0, |x| x.as_slice().iter().take_while(|x| predicate(**x)).count());
+            match take(&state, count) {
+                Some((leading, rest)) => state.set_bytes(rest).ok(leading),
+                None => state.fail(),
+            }
+        })
+    }
+    /// A length read by `length` followed by that many bytes, as in
+    /// length-prefixed records; e.g. `BytesParser::take_length(Parser::u16_be())`.
+    pub fn take_length<L>(length: Parser<L>) -> Self where L: Debug + TryInto<usize> + Clone + 'static {
+        length.and_then(|length| match length.try_into() {
+            Ok(count) => Self::bytes(count),
+            Err(_) => Self::fail(),
+        })
+    }
+}
+
+/// The first `count` bytes of the input and the rest.
+fn take(state: &State, count: usize) -> Option<(Bytes, Bytes)> {
+    state.bytes.as_ref()?.split_at(count)
+}
+
+/// A fixed-width value decoded from the next `N` bytes.
+fn fixed<T: 'static, const N: usize>(name: &'static str, decode: fn([u8; N]) -> T) -> Parser<T> {
+    Parser::init(move |state| {
+        let Some((leading, rest)) = take(&state, N) else {
+            return state.fail_expecting(Expected::Label(name.to_string()))
+        };
+        let value = <[u8; N]>::try_from(leading.as_slice()).expect("took N bytes");
+        state.set_bytes(rest).ok(decode(value))
+    })
+}
+
+macro_rules! endian_parsers {
+    ($($type:ident => $le:ident, $be:ident;)*) => {$(
+        impl Parser<$type> {
+            #[doc = concat!("A little-endian `", stringify!($type), "`.")]
+            pub fn $le() -> Self {
+                fixed(concat!("little-endian ", stringify!($type)), $type::from_le_bytes)
+            }
+            #[doc = concat!("A big-endian `", stringify!($type), "`.")]
+            pub fn $be() -> Self {
+                fixed(concat!("big-endian ", stringify!($type)), $type::from_be_bytes)
+            }
+        }
+    )*};
+}
+
+endian_parsers! {
+    u16 => u16_le, u16_be;
+    u32 => u32_le, u32_be;
+    u64 => u64_le, u64_be;
+    i16 => i16_le, i16_be;
+    i32 => i32_le, i32_be;
+    i64 => i64_le, i64_be;
+    f32 => f32_le, f32_be;
+    f64 => f64_le, f64_be;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{ByteParser, BytesParser, Expected, Parser};
+
+    #[test]
+    fn decodes_fixed_width_integers() {
+        assert_eq!(Parser::u16_be().parse_bytes(&[0x12, 0x34]).unwrap(), 0x1234);
+        assert_eq!(Parser::u32_le().parse_bytes(&[0x78, 0x56, 0x34, 0x12]).unwrap(), 0x12345678);
+        assert_eq!(Parser::i16_be().parse_bytes(&[0xff, 0xfe]).unwrap(), -2);
+        assert!(Parser::u32_le().parse_bytes(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn reads_length_prefixed_records() {
+        let record = BytesParser::take_length(ByteParser::next_byte()).map(|x| x.to_vec());
+        let parser = record.many();
+        let records = parser.parse_bytes(&[2, 0xa, 0xb, 0, 1, 0xc]).unwrap();
+        assert_eq!(records, [vec![0xa, 0xb], vec![], vec![0xc]]);
+    }
+
+    #[test]
+    fn tag_reports_the_expected_bytes() {
+        let parser = BytesParser::tag(*b"PNG").and_(ByteParser::byte(0));
+        assert!(parser.parse_bytes(b"PNG\0").is_ok());
+        let error = parser.parse_bytes(b"PNX\0").unwrap_err();
+        assert_eq!((error.position.byte_offset, error.found_byte), (0, Some(b'P')));
+        assert!(error.expected.contains(&Expected::Bytes(b"PNG".to_vec())));
+    }
+
+    #[test]
+    fn take_while_byte_may_match_nothing() {
+        let parser = BytesParser::take_while_byte(|x| x != 0).and_(ByteParser::byte(0));
+        assert_eq!(parser.parse_bytes(b"ab\0").unwrap().0.to_vec(), b"ab");
+        assert_eq!(parser.parse_bytes(b"\0").unwrap().0.len(), 0);
+    }
+}
@@ -1,9 +1,9 @@
-use crate::data::{CharParser, State};
+use crate::data::{CharParser, Expected, FatChar, State, Text};
 
 impl CharParser {
     pub fn next() -> Self {
         Self::init(|state| {
-            match state.text.uncons() {
+            match next_char(&state) {
                 Some((l, r)) => {
                     state.set_text(r).ok(l)
                 }
@@ -14,28 +14,33 @@ impl CharParser {
     pub fn char(value: impl Into<char>) -> Self {
         let value = value.into();
         Self::init(move |state| {
-            let result = state.text
-                .uncons()
+            let result = next_char(&state)
                 .filter(|(head, rest)| {
                     head.value == value
                 });
             if let Some((head, rest)) = result {
-                return State { text: rest }.ok(head)
+                return state.set_text(rest).ok(head)
             }
-            return state.fail()
+            state.fail_expecting(Expected::Char(value))
         })
     }
-    pub fn char_if(predicate: impl Fn(char) -> bool + 'static) -> Self {
+    pub fn char_if(predicate: impl Fn(char) -> bool + Send + Sync + 'static) -> Self {
         Self::init(move |state| {
-            let result = state.text
-                .uncons()
+            let result = next_char(&state)
                 .filter(|(head, rest)| {
                     predicate(head.value)
                 });
             if let Some((head, rest)) = result {
-                return State { text: rest }.ok(head)
+                return state.set_text(rest).ok(head)
             }
-            return state.fail()
+            state.fail()
         })
     }
-}
\ No newline at end of file
+}
+
+/// The next character and the rest, recording that the parse looked at it.
+fn next_char(state: &State) -> Option<(FatChar, Text)> {
+    let next = state.text.uncons();
+    state.text.examine(next.as_ref().map_or(1, |(head, _)| head.value.len_utf8()));
+    next
+}
@@ -7,7 +7,7 @@ impl ControlFlowParser {
         Self::init(move |state| {
             match (parser.clone().evaluate().binder)(state.clone()) {
                 Output::Ok { .. } => state.ok(ControlFlow::Terminate),
-                Output::Fail { .. } => state.ok(ControlFlow::NoOp),
+                Output::Fail { .. } | Output::Error { .. } => state.ok(ControlFlow::NoOp),
             }
         })
     }
@@ -15,7 +15,7 @@ impl ControlFlowParser {
         Self::init(move |state| {
             match (parser.clone().binder)(state.clone()) {
                 Output::Ok { .. } => state.ok(ControlFlow::Terminate),
-                Output::Fail { .. } => state.ok(ControlFlow::NoOp),
+                Output::Fail { .. } | Output::Error { .. } => state.ok(ControlFlow::NoOp),
             }
         })
     }
@@ -0,0 +1,218 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::data::{Output, Parser, State};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// OPERATOR TABLE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+type UnaryFold<T> = Box<dyn FnOnce(T) -> T>;
+type BinaryFold<T> = Box<dyn FnOnce(T, T) -> T>;
+
+struct Operator<F> {
+    parser: Parser<F>,
+    precedence: usize,
+    associativity: Associativity,
+}
+
+/// Prefix, postfix and infix operators for [`Parser::expression`].
+///
+/// Higher precedence binds tighter. Each operator is a `Parser<Op>` of its own
+/// `Op` type along with a fold that combines the parsed operator with its
+/// operand(s); operators are tried in the order they were added.
+pub struct OperatorTable<T> {
+    prefix: Vec<Operator<UnaryFold<T>>>,
+    postfix: Vec<Operator<UnaryFold<T>>>,
+    infix: Vec<Operator<BinaryFold<T>>>,
+}
+
+impl<T> Default for OperatorTable<T> {
+    fn default() -> Self {
+        Self { prefix: Vec::default(), postfix: Vec::default(), infix: Vec::default() }
+    }
+}
+
+impl<T: 'static> OperatorTable<T> {
+    pub fn prefix<Op: 'static>(
+        mut self,
+        operator: Parser<Op>,
+        precedence: usize,
+        fold: impl Fn(Op, T) -> T + Send + Sync + 'static,
+    ) -> Self {
+        let fold = Arc::new(fold);
+        let parser = erase(operator, move |op| {
+            let fold = fold.clone();
+            Box::new(move |operand| fold(op, operand)) as UnaryFold<T>
+        });
+        self.prefix.push(Operator { parser, precedence, associativity: Associativity::Right });
+        self
+    }
+    pub fn postfix<Op: 'static>(
+        mut self,
+        operator: Parser<Op>,
+        precedence: usize,
+        fold: impl Fn(T, Op) -> T + Send + Sync + 'static,
+    ) -> Self {
+        let fold = Arc::new(fold);
+        let parser = erase(operator, move |op| {
+            let fold = fold.clone();
+            Box::new(move |operand| fold(operand, op)) as UnaryFold<T>
+        });
+        self.postfix.push(Operator { parser, precedence, associativity: Associativity::Left });
+        self
+    }
+    pub fn infix<Op: 'static>(
+        mut self,
+        operator: Parser<Op>,
+        precedence: usize,
+        associativity: Associativity,
+        fold: impl Fn(T, Op, T) -> T + Send + Sync + 'static,
+    ) -> Self {
+        let fold = Arc::new(fold);
+        let parser = erase(operator, move |op| {
+            let fold = fold.clone();
+            Box::new(move |left, right| fold(left, op, right)) as BinaryFold<T>
+        });
+        self.infix.push(Operator { parser, precedence, associativity });
+        self
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PRATT PARSER
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<T: Debug> Parser<T> where T: Clone + 'static {
+    /// Parse operator expressions over `self` as the atom using precedence climbing.
+    pub fn expression(self, table: OperatorTable<T>) -> Self {
+        let grammar = Arc::new((self, table));
+        Self::init(move |original| {
+            let (atom, table) = grammar.as_ref();
+            match climb(atom, table, original.clone(), 0) {
+                Output::Fail { error, .. } => original.fail_with(error),
+                Output::Error { error, .. } => original.cut(error),
+                output => output,
+            }
+        })
+    }
+}
+
+fn climb<T>(atom: &Parser<T>, table: &OperatorTable<T>, state: State, min_precedence: usize) -> Output<T> {
+    // - PREFIX OPERATOR OR ATOM -
+    let (mut left, mut state) = match match_operator(&table.prefix, &state, 0) {
+        Some((operator, Output::Ok { value: fold, state })) => {
+            match climb(atom, table, state, operator.precedence) {
+                Output::Ok { value, state } => (fold(value), state),
+                output => return output,
+            }
+        }
+        Some((_, Output::Error { state, error })) => return state.cut(error),
+        Some((_, Output::Fail { .. })) | None => match (atom.binder)(state) {
+            Output::Ok { value, state } => (value, state),
+            output => return output,
+        },
+    };
+    loop {
+        // - POSTFIX OPERATOR -
+        match match_operator(&table.postfix, &state, min_precedence) {
+            Some((_, Output::Ok { value: fold, state: next })) => {
+                left = fold(left);
+                state = next;
+                continue
+            }
+            Some((_, Output::Error { state, error })) => return state.cut(error),
+            Some((_, Output::Fail { .. })) | None => (),
+        }
+        // - INFIX OPERATOR -
+        match match_operator(&table.infix, &state, min_precedence) {
+            Some((operator, Output::Ok { value: fold, state: next })) => {
+                let next_precedence = match operator.associativity {
+                    Associativity::Left => operator.precedence + 1,
+                    Associativity::Right => operator.precedence,
+                };
+                match climb(atom, table, next, next_precedence) {
+                    Output::Ok { value: right, state: next } => {
+                        left = fold(left, right);
+                        state = next;
+                        continue
+                    }
+                    output => return output,
+                }
+            }
+            Some((_, Output::Error { state, error })) => return state.cut(error),
+            Some((_, Output::Fail { .. })) | None => break,
+        }
+    }
+    state.ok(left)
+}
+
+/// The first operator at or above `min_precedence` that doesn't fail recoverably.
+///
+/// Never returns `Output::Fail`.
+fn match_operator<'a, F>(
+    operators: &'a [Operator<F>],
+    state: &State,
+    min_precedence: usize,
+) -> Option<(&'a Operator<F>, Output<F>)> {
+    operators
+        .iter()
+        .filter(|x| x.precedence >= min_precedence)
+        .find_map(|operator| {
+            match (operator.parser.binder)(state.clone()) {
+                Output::Fail { .. } => None,
+                output => Some((operator, output)),
+            }
+        })
+}
+
+fn erase<Op: 'static, F: 'static>(operator: Parser<Op>, f: impl Fn(Op) -> F + Send + Sync + 'static) -> Parser<F> {
+    Parser::init(move |state| {
+        match (operator.binder)(state) {
+            Output::Ok { value, state } => state.ok(f(value)),
+            Output::Fail { state, error } => state.fail_with(error),
+            Output::Error { state, error } => state.cut(error),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CharParser;
+
+    fn calculator() -> Parser<i64> {
+        let number = CharParser::char_if(|x| x.is_ascii_digit()).map(|x| x.value.to_digit(10).unwrap() as i64);
+        let table = OperatorTable::default()
+            .prefix(CharParser::char('-'), 3, |_, x: i64| -x)
+            .postfix(CharParser::char('!'), 4, |x: i64, _| (1..=x).product())
+            .infix(CharParser::char('+'), 1, Associativity::Left, |x, _, y| x + y)
+            .infix(CharParser::char('-'), 1, Associativity::Left, |x, _, y| x - y)
+            .infix(CharParser::char('*'), 2, Associativity::Left, |x, _, y| x * y)
+            .infix(CharParser::char('^'), 5, Associativity::Right, |x, _, y| x.pow(y as u32));
+        number.expression(table)
+    }
+
+    #[test]
+    fn binds_by_precedence_and_associativity() {
+        assert_eq!(calculator().parse("1+2*3").unwrap(), 7);
+        assert_eq!(calculator().parse("9-3-2").unwrap(), 4);
+        assert_eq!(calculator().parse("2^3^2").unwrap(), 512);
+    }
+
+    #[test]
+    fn applies_prefix_and_postfix_operators() {
+        assert_eq!(calculator().parse("-3!").unwrap(), -6);
+        assert_eq!(calculator().parse("-2*3").unwrap(), -6);
+        assert_eq!(calculator().parse("3!+1").unwrap(), 7);
+    }
+
+    #[test]
+    fn stops_at_the_first_token_that_isnt_an_operator() {
+        assert_eq!(calculator().parse_partial("1+2)").unwrap(), (3, 3));
+        assert_eq!(calculator().parse("1+").unwrap_err().position.byte_offset, 2);
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt::Debug;
+
+use crate::{data::{Output, Parser, Unit}, system::{Constant, Lazy}};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// LOOK-AHEAD
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// `!parser`, the same as `parser.not()`.
+impl<A: Debug> std::ops::Not for Parser<A> where A: Clone + 'static {
+    type Output = Parser<Unit>;
+    fn not(self) -> Parser<Unit> {
+        Parser::not(self)
+    }
+}
+
+impl<A: Debug> Parser<A> where A: Clone + 'static {
+    /// Succeed, consuming nothing, only where this parser fails; a committed
+    /// error is still an error.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Parser<Unit> {
+        Parser::<Unit>::init(move |original| {
+            match (self.binder)(original.clone()) {
+                Output::Ok { .. } => original.fail(),
+                Output::Fail { .. } => original.ok(Unit),
+                Output::Error { error, .. } => original.cut(error),
+            }
+        })
+    }
+    /// Succeed with this parser's value without consuming any input.
+    pub fn peek(self) -> Self {
+        Self::init(move |original| {
+            match (self.binder)(original.clone()) {
+                Output::Ok { value, .. } => original.ok(value),
+                Output::Fail { error, .. } => original.fail_with(error),
+                Output::Error { error, .. } => original.cut(error),
+            }
+        })
+    }
+    /// Run this parser, then require `next` to match without consuming it.
+    pub fn followed_by<B>(self, next: impl Lazy<Item = Parser<B>>) -> Self where B: Debug + Clone + 'static {
+        Self::init(move |original| {
+            match (self.binder)(original.clone()) {
+                Output::Ok { value, state } => {
+                    match (next.clone().evaluate().binder)(state.clone()) {
+                        Output::Ok { .. } => state.ok(value),
+                        Output::Fail { error, .. } => original.fail_with(error),
+                        Output::Error { error, .. } => original.cut(error),
+                    }
+                }
+                output => output,
+            }
+        })
+    }
+    /// Run this parser, then require that `next` does *not* match at that point.
+    pub fn not_followed_by<B>(self, next: impl Lazy<Item = Parser<B>>) -> Self where B: Debug + Clone + 'static {
+        Self::init(move |original| {
+            match (self.binder)(original.clone()) {
+                Output::Ok { value, state } => {
+                    match (next.clone().evaluate().binder)(state.clone()) {
+                        Output::Ok { .. } => original.fail_with(state.error()),
+                        Output::Fail { .. } | Output::Error { .. } => state.ok(value),
+                    }
+                }
+                output => output,
+            }
+        })
+    }
+    pub fn followed_by_<B>(self, next: Parser<B>) -> Self where B: Debug + Clone + 'static {
+        self.followed_by(Constant::wrap(next))
+    }
+    pub fn not_followed_by_<B>(self, next: Parser<B>) -> Self where B: Debug + Clone + 'static {
+        self.not_followed_by(Constant::wrap(next))
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// LOOK-BEHIND
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<A: Debug> Parser<A> where A: Clone + 'static {
+    /// Only run this parser where the previously consumed character satisfies
+    /// `predicate`; the start of input never does.
+    pub fn preceded_by(self, predicate: impl Fn(char) -> bool + Send + Sync + 'static) -> Self {
+        Self::init(move |state| {
+            match state.previous_char() {
+                Some(x) if predicate(x) => (self.binder)(state),
+                _ => state.fail(),
+            }
+        })
+    }
+    /// Only run this parser where the previously consumed character doesn't
+    /// satisfy `predicate`, including at the start of input.
+    pub fn not_preceded_by(self, predicate: impl Fn(char) -> bool + Send + Sync + 'static) -> Self {
+        Self::init(move |state| {
+            match state.previous_char() {
+                Some(x) if predicate(x) => state.fail(),
+                _ => (self.binder)(state),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{CharParser, Parser, TextParser};
+
+    #[test]
+    fn peek_consumes_nothing() {
+        let parser = CharParser::char('a').peek().and_(TextParser::token("ab"));
+        let (first, rest) = parser.parse("ab").unwrap();
+        assert_eq!((first.value, rest.as_str()), ('a', "ab"));
+    }
+
+    #[test]
+    fn not_succeeds_only_where_the_parser_fails() {
+        let keyword = TextParser::token("if");
+        let identifier = (!keyword).and_(CharParser::char_if(char::is_alphabetic).some());
+        assert!(identifier.parse("ok").is_ok());
+        assert!(identifier.parse("if").is_err());
+    }
+
+    #[test]
+    fn not_passes_committed_errors_through() {
+        let keyword = TextParser::token("if").and_(CharParser::char(' ').cut());
+        let parser = keyword.not().and_(CharParser::next());
+        assert!(parser.parse("x").is_ok());
+        let error = parser.parse_partial("ifx").unwrap_err();
+        assert_eq!(error.position.byte_offset, 2);
+    }
+
+    #[test]
+    fn followed_by_requires_without_consuming() {
+        let parser = CharParser::char('a').followed_by_(CharParser::char('b'));
+        assert_eq!(parser.parse_partial("ab").unwrap().1, 1);
+        assert!(parser.parse_partial("ac").is_err());
+    }
+
+    #[test]
+    fn not_followed_by_rejects_a_match() {
+        let parser = TextParser::token("if").not_followed_by_(CharParser::char_if(char::is_alphanumeric));
+        assert_eq!(parser.parse_partial("if x").unwrap().1, 2);
+        assert!(parser.parse_partial("iffy").is_err());
+    }
+
+    #[test]
+    fn preceded_by_checks_the_previous_char() {
+        let word_start = CharParser::char('b').not_preceded_by(char::is_alphabetic);
+        let inner = CharParser::char('b').preceded_by(char::is_alphabetic);
+        let parser = CharParser::char('a').and_(inner.clone());
+        assert!(parser.parse("ab").is_ok());
+        assert!(inner.parse("b").is_err());
+        assert!(word_start.parse("b").is_ok());
+        assert!(CharParser::char('a').and_(word_start).parse("ab").is_err());
+    }
+}
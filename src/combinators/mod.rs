@@ -4,10 +4,20 @@ mod text;
 mod control_flow;
 mod char;
 mod products;
+mod user_state;
+mod expression;
+mod lookahead;
+mod recovery;
+mod bytes;
 
 pub use basics::*;
 pub use sequence::*;
 pub use text::*;
 pub use control_flow::*;
 pub use char::*;
-pub use products::*;
\ No newline at end of file
+pub use products::*;
+pub use user_state::*;
+pub use expression::*;
+pub use lookahead::*;
+pub use recovery::*;
+pub use bytes::*;
\ No newline at end of file
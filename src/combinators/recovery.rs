@@ -0,0 +1,90 @@
+use std::fmt::Debug;
+
+use crate::{data::{Output, ParseError, Parser, State, Text}, system::{Constant, Lazy}};
+
+impl<A: Debug> Parser<A> where A: Clone + 'static {
+    /// Keep going past a malformed construct instead of failing the whole parse.
+    ///
+    /// Only applies when this parser fails after committing (see `Parser::cut`);
+    /// other failures are returned as-is so alternatives can still be tried. In
+    /// that case the input is skipped up to and including the next match of
+    /// `sync` (or to the end of input), the error is recorded in
+    /// `State::recovered_errors`, and `fallback` builds a value from the error
+    /// and the skipped text. A `sync` that only peeks stops before its match.
+    pub fn recover_with<S>(
+        self,
+        sync: impl Lazy<Item = Parser<S>>,
+        fallback: impl Fn(ParseError, Text) -> A + Send + Sync + 'static,
+    ) -> Self where S: Debug + Clone + 'static {
+        Self::init(move |original| {
+            let error = match (self.binder)(original.clone()) {
+                Output::Ok { value, state } => return state.ok(value),
+                Output::Fail { state, error } => return state.fail_with(error),
+                Output::Error { error, .. } => error,
+            };
+            let Some(rest) = skip_to_sync(&original, &sync.clone().evaluate()) else {
+                return original.cut(error)
+            };
+            let skipped = original.text.consumed_until(&rest);
+            let value = fallback(error.clone(), skipped);
+            original.set_text(rest).push_recovered(error).ok(value)
+        })
+    }
+    pub fn recover_with_<S>(
+        self,
+        sync: Parser<S>,
+        fallback: impl Fn(ParseError, Text) -> A + Send + Sync + 'static,
+    ) -> Self where S: Debug + Clone + 'static {
+        self.recover_with(Constant::wrap(sync), fallback)
+    }
+}
+
+/// Skip at least one character, then up to and including the next `sync`.
+///
+/// Returns `None` when there's nothing left to skip.
+fn skip_to_sync<S>(original: &State, sync: &Parser<S>) -> Option<Text> {
+    original.text.examine(1);
+    let (_, mut rest) = original.text.uncons()?;
+    loop {
+        if let Output::Ok { state, .. } = (sync.binder)(original.set_text(rest.clone())) {
+            return Some(state.text)
+        }
+        rest.examine(1);
+        match rest.uncons() {
+            Some((_, next)) => rest = next,
+            None => return Some(rest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{CharParser, Parser, Text, TextParser};
+
+    /// `(` then a committed `x` and `)`, recovering to just past the next `;`.
+    fn group() -> Parser<String> {
+        CharParser::char('(')
+            .and_(CharParser::char('x').and_(CharParser::char(')')).cut())
+            .map(|_| String::from("group"))
+            .recover_with_(CharParser::char(';'), |_, skipped: Text| skipped.to_string())
+    }
+
+    #[test]
+    fn recovers_from_committed_errors() {
+        let (result, state) = Parser::evaluate("(y;rest", group());
+        assert_eq!(result.unwrap(), "(y;");
+        assert_eq!(state.text.as_str(), "rest");
+        assert_eq!(state.recovered_errors().len(), 1);
+    }
+
+    #[test]
+    fn leaves_uncommitted_failures_to_alternatives() {
+        let parser = TextParser::token("ab")
+            .map(|x| x.to_string())
+            .recover_with_(CharParser::char(';'), |_, skipped: Text| skipped.to_string())
+            .or(TextParser::token("a;").map(|x| x.to_string()));
+        let (result, state) = Parser::evaluate("a;", parser);
+        assert_eq!(result.unwrap(), "a;");
+        assert!(state.recovered_errors().is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use crate::{data::{ControlFlow, ControlFlowParser, Output, Parser, State, TupleParser}, system::{Constant, Lazy, Thunk}};
+use crate::{data::{ControlFlow, ControlFlowParser, Output, ParseError, Parser, State, TupleParser}, system::{Constant, Lazy, Thunk}};
 
 #[derive(Default)]
 pub struct SequenceSettings {
@@ -32,32 +32,40 @@ impl<A: Debug> Parser<A> where A: Clone + 'static + Debug {
         Parser::<Vec<A>>::init(move |original| {
             let mut leading = Vec::<A>::default();
             let mut trailing: State = original.clone();
-            let mut trailing_text_length: usize = trailing.text.len();
+            let mut trailing_offset: usize = trailing.offset();
+            let mut failure: Option<ParseError> = None;
             // let mut counter = 0usize;
-            'trials : while !trailing.text.is_empty() {
+            'trials : while !trailing.examine_end() {
                 // counter += 1;
                 if let Some(terminator) = settings.until_terminator.as_ref() {
                     if let Output::Ok { value: ControlFlow::Terminate, .. } = (terminator.binder)(trailing.clone()) {
                         break 'trials;
                     }
                 }
-                if let Output::Ok { value, state } = (self.binder)(trailing.clone()) {
-                    println!("Parser.sequence {value:?} {state:?}");
-                    if trailing_text_length == state.text.len() {
-                        // DON'T LOOP FOREVER
+                match (self.binder)(trailing.clone()) {
+                    Output::Ok { value, state } => {
+                        if trailing_offset == state.offset() {
+                            // DON'T LOOP FOREVER
+                            break 'trials
+                        }
+                        trailing_offset = state.offset();
+                        leading.push(value);
+                        trailing = state;
+                        continue 'trials;
+                    }
+                    Output::Fail { error, .. } => {
+                        failure = Some(error);
                         break 'trials
                     }
-                    trailing_text_length = state.text.len();
-                    leading.push(value);
-                    trailing = state;
-                    continue 'trials;
+                    Output::Error { error, .. } => return original.cut(error),
                 }
-                break 'trials
             }
             if leading.is_empty() && !settings.allow_empty.unwrap_or(false) {
-                return original.fail()
+                return match failure {
+                    Some(error) => original.fail_with(error),
+                    None => original.fail(),
+                }
             }
-            println!("Parser.sequence FINAL STATE: {leading:?} {trailing:?}");
             trailing.ok(leading)
         })
     }
@@ -69,76 +77,240 @@ impl<A: Debug> Parser<A> where A: Clone + 'static + Debug {
         let settings = SequenceSettings::default().allow_empty(false);
         self.sequence(settings)
     }
-    pub fn many_unless<B: Debug>(
+    pub fn many_unless<B>(
         self,
         other: impl Lazy<Item = Parser<B>>
-    ) -> TupleParser<Vec<A>, Option<B>> where B: 'static + Clone {
+    ) -> TupleParser<Vec<A>, Option<B>> where B: Debug + 'static + Clone {
         let settings = SequenceSettings::default()
             .allow_empty(true)
             .until_terminator(ControlFlowParser::terminate_if_ok(other.clone()));
         self.sequence(settings).and(other.map(|o| o.optional()))
     }
-    pub fn some_unless<B: Debug>(
+    pub fn some_unless<B>(
         self,
         other: impl Lazy<Item = Parser<B>>
-    ) -> TupleParser<Vec<A>, Option<B>> where B: 'static + Clone {
+    ) -> TupleParser<Vec<A>, Option<B>> where B: Debug + 'static + Clone {
         let settings = SequenceSettings::default()
             .allow_empty(false)
             .until_terminator(ControlFlowParser::terminate_if_ok(other.clone()));
         self.sequence(settings).and(other.map(|o| o.optional()))
     }
-    pub fn many_till<B: Debug>(
+    pub fn many_till<B>(
         self,
         other: impl Lazy<Item = Parser<B>>
-    ) -> TupleParser<Vec<A>, B> where B: 'static + Clone {
+    ) -> TupleParser<Vec<A>, B> where B: Debug + 'static + Clone {
         let settings = SequenceSettings::default()
             .allow_empty(true)
             .until_terminator(ControlFlowParser::terminate_if_ok(other.clone()));
         self.sequence(settings).and(other)
     }
-    pub fn some_till<B: Debug>(
+    pub fn some_till<B>(
         self,
         other: impl Lazy<Item = Parser<B>>
-    ) -> TupleParser<Vec<A>, B> where B: 'static + Clone {
+    ) -> TupleParser<Vec<A>, B> where B: Debug + 'static + Clone {
         let settings = SequenceSettings::default()
             .allow_empty(false)
             .until_terminator(ControlFlowParser::terminate_if_ok(other.clone()));
         self.sequence(settings).and(other)
     }
-    pub fn many_unless_<B: Debug>(
+    pub fn many_unless_<B>(
         self,
         other: Parser<B>,
-    ) -> TupleParser<Vec<A>, Option<B>> where B: 'static + Clone {
+    ) -> TupleParser<Vec<A>, Option<B>> where B: Debug + 'static + Clone {
         let settings = SequenceSettings::default()
             .allow_empty(true)
             .until_terminator(ControlFlowParser::terminate_if_ok_(other.clone()));
         self.sequence(settings).and_(other.clone().optional())
     }
-    pub fn some_unless_<B: Debug>(
+    pub fn some_unless_<B>(
         self,
         other: Parser<B>,
-    ) -> TupleParser<Vec<A>, Option<B>> where B: 'static + Clone {
+    ) -> TupleParser<Vec<A>, Option<B>> where B: Debug + 'static + Clone {
         let settings = SequenceSettings::default()
             .allow_empty(false)
             .until_terminator(ControlFlowParser::terminate_if_ok_(other.clone()));
         self.sequence(settings).and_(other.clone().optional())
     }
-    pub fn many_till_<B: Debug>(
+    pub fn many_till_<B>(
         self,
         other: Parser<B>,
-    ) -> TupleParser<Vec<A>, B> where B: 'static + Clone {
+    ) -> TupleParser<Vec<A>, B> where B: Debug + 'static + Clone {
         let settings = SequenceSettings::default()
             .allow_empty(true)
             .until_terminator(ControlFlowParser::terminate_if_ok_(other.clone()));
         self.sequence(settings).and_(other.clone())
     }
-    pub fn some_till_<B: Debug>(
+    pub fn some_till_<B>(
         self,
         other: Parser<B>,
-    ) -> TupleParser<Vec<A>, B> where B: 'static + Clone {
+    ) -> TupleParser<Vec<A>, B> where B: Debug + 'static + Clone {
         let settings = SequenceSettings::default()
             .allow_empty(false)
             .until_terminator(ControlFlowParser::terminate_if_ok_(other.clone()));
         self.sequence(settings).and_(other.clone())
     }
 }
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// SEPARATED LISTS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Where separators may appear relative to the items of a separated list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trailing {
+    /// `a,b,c`
+    Forbidden,
+    /// `a,b,c` or `a,b,c,`
+    Optional,
+    /// `a,b,c,`
+    Required,
+}
+
+impl<A: Debug> Parser<A> where A: Clone + 'static + Debug {
+    /// Zero or more items separated by `separator`; returns the items and separators.
+    pub fn sep_by<S>(self, separator: impl Lazy<Item = Parser<S>>) -> TupleParser<Vec<A>, Vec<S>> where S: Debug + Clone + 'static {
+        self.separated(separator, true, Trailing::Forbidden)
+    }
+    /// One or more items separated by `separator`.
+    pub fn sep_by1<S>(self, separator: impl Lazy<Item = Parser<S>>) -> TupleParser<Vec<A>, Vec<S>> where S: Debug + Clone + 'static {
+        self.separated(separator, false, Trailing::Forbidden)
+    }
+    /// Like `sep_by`, but a trailing separator is allowed.
+    pub fn sep_end_by<S>(self, separator: impl Lazy<Item = Parser<S>>) -> TupleParser<Vec<A>, Vec<S>> where S: Debug + Clone + 'static {
+        self.separated(separator, true, Trailing::Optional)
+    }
+    /// Zero or more items, each followed by `separator`.
+    pub fn end_by<S>(self, separator: impl Lazy<Item = Parser<S>>) -> TupleParser<Vec<A>, Vec<S>> where S: Debug + Clone + 'static {
+        self.separated(separator, true, Trailing::Required)
+    }
+    pub fn sep_by_<S>(self, separator: Parser<S>) -> TupleParser<Vec<A>, Vec<S>> where S: Debug + Clone + 'static {
+        self.separated(Constant::wrap(separator), true, Trailing::Forbidden)
+    }
+    pub fn sep_by1_<S>(self, separator: Parser<S>) -> TupleParser<Vec<A>, Vec<S>> where S: Debug + Clone + 'static {
+        self.separated(Constant::wrap(separator), false, Trailing::Forbidden)
+    }
+    pub fn sep_end_by_<S>(self, separator: Parser<S>) -> TupleParser<Vec<A>, Vec<S>> where S: Debug + Clone + 'static {
+        self.separated(Constant::wrap(separator), true, Trailing::Optional)
+    }
+    pub fn end_by_<S>(self, separator: Parser<S>) -> TupleParser<Vec<A>, Vec<S>> where S: Debug + Clone + 'static {
+        self.separated(Constant::wrap(separator), true, Trailing::Required)
+    }
+    fn separated<S>(
+        self,
+        separator: impl Lazy<Item = Parser<S>>,
+        allow_empty: bool,
+        trailing: Trailing,
+    ) -> TupleParser<Vec<A>, Vec<S>> where S: Clone + 'static {
+        TupleParser::<Vec<A>, Vec<S>>::init(move |original| {
+            let separator = separator.clone().evaluate();
+            let mut items = Vec::<A>::default();
+            let mut separators = Vec::<S>::default();
+            let mut state = original.clone();
+            let mut after_last_item = original.clone();
+            let mut failure: Option<ParseError> = None;
+            'items: loop {
+                let before_item = state.clone();
+                // - ITEM -
+                match (self.binder)(state.clone()) {
+                    Output::Ok { value, state: next } => {
+                        items.push(value);
+                        state = next;
+                    }
+                    Output::Fail { error, .. } => {
+                        failure = Some(error);
+                        if trailing == Trailing::Forbidden && !separators.is_empty() {
+                            // A SEPARATOR MUST BE FOLLOWED BY AN ITEM
+                            separators.pop();
+                            state = after_last_item;
+                        }
+                        break 'items
+                    }
+                    Output::Error { error, .. } => return original.cut(error),
+                }
+                after_last_item = state.clone();
+                // - SEPARATOR -
+                match (separator.binder)(state.clone()) {
+                    Output::Ok { state: next, .. } if next.offset() == before_item.offset() => {
+                        // DON'T LOOP FOREVER, NOR KEEP A SEPARATOR NO ITEM FOLLOWS
+                        if trailing == Trailing::Required {
+                            items.pop();
+                        }
+                        break 'items
+                    }
+                    Output::Ok { value, state: next } => {
+                        separators.push(value);
+                        state = next;
+                    }
+                    Output::Fail { .. } => {
+                        if trailing == Trailing::Required {
+                            // AN ITEM MUST BE FOLLOWED BY A SEPARATOR
+                            items.pop();
+                            state = before_item;
+                        }
+                        break 'items
+                    }
+                    Output::Error { error, .. } => return original.cut(error),
+                }
+            }
+            if items.is_empty() && !allow_empty {
+                return match failure {
+                    Some(error) => original.fail_with(error),
+                    None => original.fail(),
+                }
+            }
+            state.ok((items, separators))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{CharParser, Parser, VecParser};
+
+    fn digits() -> CharParser {
+        CharParser::char_if(|x| x.is_ascii_digit())
+    }
+
+    fn values(parser: VecParser<crate::data::FatChar>) -> Parser<String> {
+        parser.map(|x| x.into_iter().map(|x| x.value).collect())
+    }
+
+    #[test]
+    fn sep_by_forbids_a_trailing_separator() {
+        let parser = values(digits().sep_by_(CharParser::char(',')).map(|(items, _)| items));
+        assert_eq!(parser.parse("").unwrap(), "");
+        assert_eq!(parser.parse("1,2,3").unwrap(), "123");
+        assert_eq!(parser.parse_partial("1,2,").unwrap(), (String::from("12"), 3));
+    }
+
+    #[test]
+    fn sep_by1_needs_an_item() {
+        let parser = digits().sep_by1_(CharParser::char(','));
+        assert!(parser.parse("").is_err());
+        let (items, separators) = parser.parse("1,2").unwrap();
+        assert_eq!((items.len(), separators.len()), (2, 1));
+    }
+
+    #[test]
+    fn sep_end_by_allows_a_trailing_separator() {
+        let parser = values(digits().sep_end_by_(CharParser::char(';')).map(|(items, _)| items));
+        assert_eq!(parser.parse("1;2").unwrap(), "12");
+        assert_eq!(parser.parse("1;2;").unwrap(), "12");
+    }
+
+    #[test]
+    fn end_by_requires_a_trailing_separator() {
+        let parser = values(digits().end_by_(CharParser::char(';')).map(|(items, _)| items));
+        assert_eq!(parser.parse("1;2;").unwrap(), "12");
+        assert_eq!(parser.parse_partial("1;2").unwrap(), (String::from("1"), 2));
+    }
+
+    #[test]
+    fn separated_lists_stop_without_a_dangling_separator_when_nothing_is_consumed() {
+        let item = CharParser::char('a').optional();
+        let separator = CharParser::char(',').optional();
+        let (items, separators) = item.clone().sep_by_(separator.clone()).parse_partial("x").unwrap().0;
+        assert_eq!((items.len(), separators.len()), (1, 0));
+        let (items, separators) = item.end_by_(separator).parse_partial("x").unwrap().0;
+        assert_eq!((items.len(), separators.len()), (0, 0));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::data::{State, TextParser};
+use crate::data::{Expected, State, TextParser};
 
 
 
@@ -6,12 +6,12 @@ impl TextParser {
     pub fn token(value: impl Into<String>) -> Self {
         let value = value.into();
         Self::init(move |state| {
+            state.text.examine(value.len());
             if let Some((prefix, rest)) = state.text.pop_prefix(&value) {
                 assert!(prefix.to_string() == value);
-                println!("TextParser.token {prefix} => {rest:?}");
-                return State { text: rest }.ok(prefix)
+                return state.set_text(rest).ok(prefix)
             }
-            state.fail()
+            state.fail_expecting(Expected::Token(value.clone()))
         })
     }
 }
\ No newline at end of file
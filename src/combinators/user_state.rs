@@ -0,0 +1,79 @@
+use std::fmt::Debug;
+
+use crate::data::{Output, Parser, Unit, UserState};
+
+impl<S> Parser<S> where S: Clone + Default + 'static {
+    /// The current user state of type `S`, or `S::default()` if none was put.
+    pub fn get_state() -> Self {
+        Self::init(|state| {
+            let value = state.user.get::<S>().unwrap_or_default();
+            state.ok(value)
+        })
+    }
+}
+
+impl Parser<Unit> {
+    pub fn put_state<S: Clone + Send + Sync + 'static>(value: S) -> Self {
+        Self::init(move |state| {
+            let user = state.user.insert(value.clone());
+            state.set_user(user).ok(Unit)
+        })
+    }
+    pub fn modify_state<S: Clone + Default + 'static>(f: impl Fn(S) -> S + Send + Sync + 'static) -> Self {
+        Self::init(move |state| {
+            let value = f(state.user.get::<S>().unwrap_or_default());
+            let user = state.user.insert(value);
+            state.set_user(user).ok(Unit)
+        })
+    }
+}
+
+impl<A: Debug> Parser<A> where A: Clone + 'static {
+    /// Run this parser with the user state of type `S` set to `value`, then
+    /// restore whatever `S` was before, whether or not it succeeded.
+    pub fn with_local_state<S: Clone + Send + Sync + 'static>(self, value: S) -> Self {
+        Self::init(move |state| {
+            let outer = state.user.get::<S>();
+            let restore = move |user: UserState| match outer.clone() {
+                Some(outer) => user.insert(outer),
+                None => user.remove::<S>(),
+            };
+            let inner = state.set_user(state.user.insert(value.clone()));
+            match (self.binder)(inner) {
+                Output::Ok { value, state } => state.set_user(restore(state.user.clone())).ok(value),
+                Output::Fail { state, error } => state.set_user(restore(state.user.clone())).fail_with(error),
+                Output::Error { state, error } => state.set_user(restore(state.user.clone())).cut(error),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{CharParser, Parser, Unit};
+
+    /// Counts the `a`s it consumes in a `u32` user state.
+    fn counted() -> Parser<u32> {
+        CharParser::char('a')
+            .and_(Parser::<Unit>::modify_state(|x: u32| x + 1))
+            .many()
+            .and_(Parser::<u32>::get_state())
+            .map(|(_, count)| count)
+    }
+
+    #[test]
+    fn state_is_threaded_through_a_parse() {
+        assert_eq!(counted().parse("aaa").unwrap(), 3);
+        let parser = Parser::<Unit>::put_state(10u32).and_(counted()).map(|(_, count)| count);
+        assert_eq!(parser.parse("aa").unwrap(), 12);
+    }
+
+    #[test]
+    fn local_state_is_restored_afterwards() {
+        let parser = Parser::<Unit>::put_state(String::from("outer"))
+            .and_(Parser::<String>::get_state().with_local_state(String::from("inner")))
+            .and_(Parser::<String>::get_state())
+            .map(|((_, inner), outer)| (inner, outer));
+        assert_eq!(parser.parse("").unwrap(), (String::from("inner"), String::from("outer")));
+    }
+}
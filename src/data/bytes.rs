@@ -0,0 +1,91 @@
+use std::sync::Arc;
+use tree_formatter::{PrettyTree, ToPrettyTree};
+
+use super::PositionIndex;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// BYTES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A view of a byte range of binary input; the byte counterpart of `Text`.
+#[derive(Clone)]
+pub struct Bytes {
+    data: Arc<[u8]>,
+    start: usize,
+    end: usize,
+}
+
+impl Bytes {
+    pub fn new(data: impl Into<Arc<[u8]>>) -> Self {
+        let data = data.into();
+        let end = data.len();
+        Self { data, start: 0, end }
+    }
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data[self.start..self.end]
+    }
+    /// The offset of the first byte in the input.
+    pub fn offset(&self) -> usize {
+        self.start
+    }
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+    /// Binary input has no lines; it's treated as a single line with one
+    /// column per byte.
+    pub fn position(&self) -> PositionIndex {
+        PositionIndex {
+            grapheme_offset: self.start,
+            scalar_offset: self.start,
+            byte_offset: self.start,
+            utf16_offset: self.start,
+            line_offset: 0,
+            column_offset: self.start,
+        }
+    }
+    pub fn first(&self) -> Option<u8> {
+        self.as_slice().first().copied()
+    }
+    pub fn uncons(&self) -> Option<(u8, Self)> {
+        let first = self.first()?;
+        Some((first, self.split_at(1)?.1))
+    }
+    /// The first `count` bytes and the rest, or `None` if there are fewer.
+    pub fn split_at(&self, count: usize) -> Option<(Self, Self)> {
+        if count > self.len() {
+            return None
+        }
+        let middle = self.start + count;
+        let leading = Self { data: self.data.clone(), start: self.start, end: middle };
+        let trailing = Self { data: self.data.clone(), start: middle, end: self.end };
+        Some((leading, trailing))
+    }
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.as_slice().to_vec()
+    }
+}
+
+impl std::fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f   .debug_tuple("Bytes")
+            .field(&format_bytes(self.as_slice()))
+            .finish()
+    }
+}
+
+/// `[0x01 0xff]`, as bytes are shown in debug output and errors.
+pub(crate) fn format_bytes(bytes: &[u8]) -> String {
+    let bytes = bytes.iter().map(|x| format!("{x:#04x}")).collect::<Vec<_>>();
+    format!("[{}]", bytes.join(" "))
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DEBUG
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl ToPrettyTree for Bytes {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        PrettyTree::value(format_bytes(self.as_slice()))
+    }
+}
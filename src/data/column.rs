@@ -0,0 +1,77 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// COLUMN MODE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The unit a column is counted in, from the start of its line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnMode {
+    /// Extended grapheme clusters; what `PositionIndex::column_offset` uses.
+    #[default]
+    Graphemes,
+    /// Unicode scalar values.
+    Chars,
+    /// UTF-16 code units, as used by the Language Server Protocol.
+    Utf16,
+    /// Terminal cells as measured by `unicode-width`: wide (e.g. CJK) graphemes
+    /// take two, combining marks none, and tabs advance to the next multiple
+    /// of `tab_width`.
+    DisplayWidth { tab_width: usize },
+}
+
+impl ColumnMode {
+    /// Measure `line_prefix`, which must start at the beginning of a line.
+    pub fn measure(self, line_prefix: &str) -> usize {
+        match self {
+            ColumnMode::Graphemes => line_prefix.graphemes(true).count(),
+            ColumnMode::Chars => line_prefix.chars().count(),
+            ColumnMode::Utf16 => line_prefix.encode_utf16().count(),
+            ColumnMode::DisplayWidth { tab_width } => {
+                line_prefix.graphemes(true).fold(0, |column, grapheme| {
+                    advance(column, grapheme, tab_width)
+                })
+            }
+        }
+    }
+}
+
+/// The display column after `grapheme` when it starts at `column`.
+pub(crate) fn advance(column: usize, grapheme: &str, tab_width: usize) -> usize {
+    match grapheme {
+        "\t" if tab_width == 0 => column,
+        "\t" => (column / tab_width + 1) * tab_width,
+        _ => column + grapheme.width(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_in_each_mode() {
+        let line = "e\u{301}😀漢";
+        assert_eq!(ColumnMode::Graphemes.measure(line), 3);
+        assert_eq!(ColumnMode::Chars.measure(line), 4);
+        assert_eq!(ColumnMode::Utf16.measure(line), 5);
+        assert_eq!(ColumnMode::DisplayWidth { tab_width: 4 }.measure(line), 5);
+    }
+
+    #[test]
+    fn emoji_sequences_are_two_cells_wide() {
+        let mode = ColumnMode::DisplayWidth { tab_width: 4 };
+        assert_eq!(mode.measure("\u{2764}\u{FE0F}"), 2);
+        assert_eq!(mode.measure("\u{1F469}\u{200D}\u{1F4BB}"), 2);
+        assert_eq!(mode.measure("\u{1F44D}\u{1F3FD}"), 2);
+    }
+
+    #[test]
+    fn tabs_advance_to_the_next_stop() {
+        let mode = ColumnMode::DisplayWidth { tab_width: 4 };
+        assert_eq!(mode.measure("\t"), 4);
+        assert_eq!(mode.measure("ab\t"), 4);
+        assert_eq!(mode.measure("abcd\tx"), 9);
+        assert_eq!(ColumnMode::DisplayWidth { tab_width: 0 }.measure("a\tb"), 2);
+    }
+}
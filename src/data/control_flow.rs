@@ -1,17 +1,12 @@
 use tree_formatter::{PrettyTree, ToPrettyTree};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum ControlFlow {
+    #[default]
     NoOp,
     Terminate,
 }
 
-impl Default for ControlFlow {
-    fn default() -> Self {
-        ControlFlow::NoOp
-    }
-}
-
 impl ToPrettyTree for ControlFlow {
     fn to_pretty_tree(&self) -> tree_formatter::PrettyTree {
         match self {
@@ -0,0 +1,156 @@
+use std::collections::BTreeSet;
+use itertools::Itertools;
+use tree_formatter::{PrettyTree, ToPrettyTree};
+
+use super::{format_bytes, format_context, PositionIndex};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// EXPECTED ITEMS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Something a parser would have accepted at the point of failure.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Expected {
+    Char(char),
+    Token(String),
+    Label(String),
+    Byte(u8),
+    /// A run of bytes, such as a magic number.
+    Bytes(Vec<u8>),
+    EndOfInput,
+}
+
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Char(x) => write!(f, "{x:?}"),
+            Self::Token(x) => write!(f, "{x:?}"),
+            Self::Label(x) => write!(f, "{x}"),
+            Self::Byte(x) => write!(f, "byte {x:#04x}"),
+            Self::Bytes(x) => write!(f, "bytes {}", format_bytes(x)),
+            Self::EndOfInput => write!(f, "end of input"),
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PARSE ERROR
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The furthest failure reached while parsing.
+///
+/// Errors produced by alternative branches are combined with [`ParseError::merge`],
+/// which keeps whichever branch got further into the input and unions the
+/// expected sets of branches that failed at the same position.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub position: PositionIndex,
+    pub expected: BTreeSet<Expected>,
+    /// The character at `position`, or `None` at the end of input.
+    pub found: Option<char>,
+    /// The byte at `position` when parsing binary input; `found` is then `None`.
+    pub found_byte: Option<u8>,
+    /// The `Parser::context` names active at the failure, innermost first.
+    pub context: Vec<String>,
+}
+
+impl ParseError {
+    pub fn new(position: PositionIndex, found: Option<char>) -> Self {
+        Self { position, expected: BTreeSet::default(), found, found_byte: None, context: Vec::default() }
+    }
+    pub fn expecting(mut self, item: Expected) -> Self {
+        self.expected.insert(item);
+        self
+    }
+    pub fn merge(mut self, other: Self) -> Self {
+        use std::cmp::Ordering;
+        match self.position.byte_offset.cmp(&other.position.byte_offset) {
+            Ordering::Greater => self,
+            Ordering::Less => other,
+            Ordering::Equal => {
+                self.expected.extend(other.expected);
+                if self.context.is_empty() {
+                    self.context = other.context;
+                }
+                self
+            }
+        }
+    }
+    /// The expected/found summary, without the context trace.
+    pub fn message(&self) -> String {
+        let found = self.describe_found();
+        let expected = self.expected.iter().map(ToString::to_string).collect_vec();
+        match expected.as_slice() {
+            [] => format!("unexpected {found}"),
+            [single] => format!("expected {single}, found {found}"),
+            many => format!("expected one of {}, found {found}", many.join(", ")),
+        }
+    }
+    /// The context stack rendered as `in Emphasis > in InlineSequence`.
+    pub fn context_trace(&self) -> Option<String> {
+        if self.context.is_empty() {
+            return None
+        }
+        Some(format_context(&self.context))
+    }
+    fn describe_found(&self) -> String {
+        match (self.found, self.found_byte) {
+            (Some(x), _) => format!("{x:?}"),
+            (None, Some(x)) => format!("byte {x:#04x}"),
+            (None, None) => String::from("end of input"),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.context_trace() {
+            Some(trace) => write!(f, "{} ({trace})", self.message()),
+            None => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DEBUG
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl ToPrettyTree for Expected {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        PrettyTree::value(self)
+    }
+}
+impl ToPrettyTree for ParseError {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        let expected = self.expected.iter().map(ToString::to_string).join(", ");
+        let found = self.describe_found();
+        PrettyTree::branch_of("ParseError", vec![
+            PrettyTree::key_value("position", self.position),
+            PrettyTree::key_value("expected", PrettyTree::value(format!("[{expected}]"))),
+            PrettyTree::key_value("found", PrettyTree::value(found)),
+            PrettyTree::key_value("context", PrettyTree::value(format_context(&self.context))),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(byte_offset: usize, expected: Expected) -> ParseError {
+        let position = PositionIndex { byte_offset, ..PositionIndex::ZERO };
+        ParseError::new(position, None).expecting(expected)
+    }
+
+    #[test]
+    fn merge_keeps_the_furthest_error() {
+        let error = at(1, Expected::Char('a')).merge(at(2, Expected::Char('b')));
+        assert_eq!(error.position.byte_offset, 2);
+        assert_eq!(error.message(), "expected 'b', found end of input");
+    }
+
+    #[test]
+    fn merge_unions_what_was_expected_at_the_same_position() {
+        let error = at(1, Expected::Char('a')).merge(at(1, Expected::Token(String::from("bc"))));
+        assert_eq!(error.message(), "expected one of 'a', \"bc\", found end of input");
+    }
+}
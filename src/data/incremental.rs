@@ -0,0 +1,337 @@
+use std::ops::Range;
+
+use super::{Output, ParseError, Parser, PositionIndex, Source, State, Text, Unit};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// TEXT EDIT
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Replaces the bytes in `range` of a document with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self { range, replacement: replacement.into() }
+    }
+    /// `source` with this edit applied, or `None` if `range` is reversed,
+    /// past the end of `source` or not on char boundaries.
+    pub fn apply(&self, source: &str) -> Option<String> {
+        source.get(self.range.clone())?;
+        let mut text = source.to_string();
+        text.replace_range(self.range.clone(), &self.replacement);
+        Some(text)
+    }
+    /// Where a byte at or after the end of `range` ends up after the edit.
+    fn shift(&self, byte_offset: usize) -> usize {
+        byte_offset - self.range.end + self.range.start + self.replacement.len()
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DOCUMENT
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A source parsed as a run of top-level items (e.g. `Block`s), each from a
+/// fresh state, kept so it can be re-parsed after an edit.
+///
+/// Every node points into the current source: nodes reused by `reparse` are
+/// moved onto it with `Rebase`, so positions read from them are up to date
+/// and the previous source isn't kept alive.
+#[derive(Debug, Clone)]
+pub struct Document<T> {
+    source: Source,
+    nodes: Vec<Node<T>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node<T> {
+    pub value: T,
+    /// The bytes of the current source this node was parsed from.
+    pub range: Range<usize>,
+    /// One past the furthest byte its parser looked at.
+    examined: usize,
+}
+
+/// The result of `Document::reparse`.
+#[derive(Debug, Clone)]
+pub struct Reparse<T> {
+    pub document: Document<T>,
+    /// Indices of the nodes of `document` that were parsed again.
+    pub changed: Range<usize>,
+    /// Indices of the nodes of the previous document they replace.
+    pub removed: Range<usize>,
+}
+
+impl<T> Document<T> {
+    /// Parse all of `source` with `item`, one item after another.
+    pub fn parse(source: impl AsRef<str>, item: &Parser<T>) -> Result<Self, ParseError> {
+        let source = Source::new(source.as_ref());
+        let mut nodes = Vec::new();
+        let mut offset = 0;
+        while offset < source.len() {
+            let node = parse_node(&source, offset, item)?;
+            offset = node.range.end;
+            nodes.push(node);
+        }
+        Ok(Self { source, nodes })
+    }
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+    pub fn nodes(&self) -> &[Node<T>] {
+        &self.nodes
+    }
+    /// Parse the edited source, reusing every node whose parse couldn't have
+    /// been affected by `edit`. `item` must be the parser this document was
+    /// built with.
+    ///
+    /// Leading nodes are kept if their parser never looked at the edited
+    /// bytes. From there items are parsed again until one ends where an old
+    /// node following the edit starts (with the same character before it, for
+    /// lookbehind); that node and the rest are reused, shifted past the edit.
+    pub fn reparse(&self, edit: &TextEdit, item: &Parser<T>) -> Result<Reparse<T>, ReparseError> where T: Rebase {
+        let Some(source) = edit.apply(self.source.as_str()) else {
+            return Err(ReparseError::InvalidEdit(edit.clone()))
+        };
+        let source = Source::new(source);
+        let kept = self.nodes
+            .iter()
+            .take_while(|x| x.examined <= edit.range.start)
+            .count();
+        let mut nodes = self.nodes[..kept]
+            .iter()
+            .map(|x| x.rebase(&Relocation::new(&self.source, &source, x.range.start, x.range.start)))
+            .collect::<Vec<_>>();
+        let mut offset = nodes.last().map_or(0, |x| x.range.end);
+        let mut candidate = self.nodes.partition_point(|x| x.range.start < edit.range.end);
+        let mut resumed = None;
+        while offset < source.len() {
+            while self.nodes.get(candidate).is_some_and(|x| edit.shift(x.range.start) < offset) {
+                candidate += 1;
+            }
+            let resumable = self.nodes.get(candidate).is_some_and(|x| {
+                let previous = self.source.as_str()[..x.range.start].chars().next_back();
+                edit.shift(x.range.start) == offset && previous == source.as_str()[..offset].chars().next_back()
+            });
+            if resumable {
+                resumed = Some(candidate);
+                break
+            }
+            let node = parse_node(&source, offset, item).map_err(ReparseError::Parse)?;
+            offset = node.range.end;
+            nodes.push(node);
+        }
+        let changed = kept..nodes.len();
+        let removed = kept..resumed.unwrap_or(self.nodes.len());
+        for node in &self.nodes[removed.end..] {
+            let relocation = Relocation::new(&self.source, &source, node.range.start, edit.shift(node.range.start));
+            nodes.push(node.rebase(&relocation));
+        }
+        let document = Self { source, nodes };
+        Ok(Reparse { document, changed, removed })
+    }
+}
+
+impl<T: Rebase> Rebase for Node<T> {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Node {
+            value: self.value.rebase(relocation),
+            range: relocation.offset(self.range.start)..relocation.offset(self.range.end),
+            examined: relocation.offset(self.examined),
+        }
+    }
+}
+
+/// Parse one item at `offset`, noting how far it looked.
+fn parse_node<T>(source: &Source, offset: usize, item: &Parser<T>) -> Result<Node<T>, ParseError> {
+    source.take_examined();
+    let output = (item.binder)(State::from_text(Text::from_offset(source.clone(), offset)));
+    let examined = source.take_examined();
+    match output {
+        // - AN ITEM THAT CONSUMES NOTHING WOULD REPEAT FOREVER -
+        Output::Ok { state, .. } if state.text.offset() == offset => Err(state.error()),
+        Output::Ok { value, state } => {
+            let range = offset..state.text.offset();
+            let examined = examined.max(range.end);
+            Ok(Node { value, range, examined })
+        }
+        Output::Fail { error, .. } => Err(error),
+        Output::Error { error, .. } => Err(error),
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// REPARSE ERROR
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug)]
+pub enum ReparseError {
+    /// The edit's range is reversed, past the end of the source or not on
+    /// char boundaries.
+    InvalidEdit(TextEdit),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for ReparseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidEdit(edit) => write!(f, "edit range {:?} doesn't fit the source", edit.range),
+            Self::Parse(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for ReparseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidEdit(_) => None,
+            Self::Parse(error) => Some(error),
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// REBASE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A value that can be moved onto an edited copy of the source it was parsed
+/// from, so `Document::reparse` can reuse it.
+///
+/// Implementations rebuild the value with every `Text`, `FatChar` and
+/// position passed through the `Relocation`.
+pub trait Rebase {
+    fn rebase(&self, relocation: &Relocation) -> Self;
+}
+
+/// Moves a reused node from the previous source to the current one.
+///
+/// Only the node's own bytes and the char before it are known to be unchanged,
+/// so values should only hold `Text`s within that region.
+#[derive(Debug, Clone)]
+pub struct Relocation {
+    previous: Source,
+    current: Source,
+    /// Where the node starts in the previous source.
+    from: usize,
+    /// Where the node starts in the current source.
+    to: usize,
+}
+
+impl Relocation {
+    fn new(previous: &Source, current: &Source, from: usize, to: usize) -> Self {
+        Self { previous: previous.clone(), current: current.clone(), from, to }
+    }
+    /// Whether `source` is the one being relocated from; `Text`s of other
+    /// sources are left as they are.
+    pub fn applies_to(&self, source: &Source) -> bool {
+        self.previous.ptr_eq(source)
+    }
+    pub fn current(&self) -> &Source {
+        &self.current
+    }
+    /// Where a byte of the node ends up in the current source.
+    pub fn offset(&self, byte_offset: usize) -> usize {
+        byte_offset + self.to - self.from
+    }
+    /// `position` worked out again in the current source.
+    pub fn position(&self, position: PositionIndex) -> PositionIndex {
+        self.current.position(self.offset(position.byte_offset))
+    }
+}
+
+impl<T: Rebase> Rebase for Vec<T> {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        self.iter().map(|x| x.rebase(relocation)).collect()
+    }
+}
+impl<T: Rebase> Rebase for Option<T> {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        self.as_ref().map(|x| x.rebase(relocation))
+    }
+}
+impl<T: Rebase> Rebase for Box<T> {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Box::new(self.as_ref().rebase(relocation))
+    }
+}
+impl<A: Rebase, B: Rebase> Rebase for (A, B) {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        (self.0.rebase(relocation), self.1.rebase(relocation))
+    }
+}
+impl<A: Rebase, B: Rebase, C: Rebase> Rebase for (A, B, C) {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        (self.0.rebase(relocation), self.1.rebase(relocation), self.2.rebase(relocation))
+    }
+}
+
+/// Values that don't refer to the source are reused as they are.
+macro_rules! rebase_as_clone {
+    ($($type:ty),*) => {$(
+        impl Rebase for $type {
+            fn rebase(&self, _: &Relocation) -> Self {
+                self.clone()
+            }
+        }
+    )*};
+}
+
+rebase_as_clone!(Unit, String, char, bool, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CharParser;
+
+    /// One line including its line break.
+    fn line() -> Parser<Text> {
+        CharParser::char_if(|x| x != '\n')
+            .many()
+            .and_(CharParser::char('\n').optional())
+            .map_with_start(|start, (chars, end)| Text::from_chars(start, chars.into_iter().chain(end)).unwrap())
+    }
+
+    fn values(document: &Document<Text>) -> Vec<(&str, Range<usize>)> {
+        document.nodes().iter().map(|x| (x.value.as_str(), x.range.clone())).collect()
+    }
+
+    #[test]
+    fn reparses_only_the_edited_nodes() {
+        let document = Document::parse("a\nbb\nc\n", &line()).unwrap();
+        let reparse = document.reparse(&TextEdit::new(2..4, "x"), &line()).unwrap();
+        assert_eq!((reparse.changed.clone(), reparse.removed.clone()), (1..2, 1..2));
+        assert_eq!(values(&reparse.document), [("a\n", 0..2), ("x\n", 2..4), ("c\n", 4..6)]);
+    }
+
+    #[test]
+    fn an_edit_may_merge_nodes() {
+        let document = Document::parse("a\nb\nc\n", &line()).unwrap();
+        let reparse = document.reparse(&TextEdit::new(1..2, ""), &line()).unwrap();
+        assert_eq!((reparse.changed.clone(), reparse.removed.clone()), (0..1, 0..2));
+        assert_eq!(values(&reparse.document), [("ab\n", 0..3), ("c\n", 3..5)]);
+        let fresh = Document::parse(reparse.document.source().as_str(), &line()).unwrap();
+        assert_eq!(values(&fresh), values(&reparse.document));
+    }
+
+    #[test]
+    fn reused_nodes_point_into_the_current_source() {
+        let document = Document::parse("a\nb\nc\n", &line()).unwrap();
+        let reparse = document.reparse(&TextEdit::new(2..2, "x\n"), &line()).unwrap();
+        assert_eq!((reparse.changed.clone(), reparse.removed.clone()), (1..2, 1..1));
+        let source = reparse.document.source();
+        for (line_offset, node) in reparse.document.nodes().iter().enumerate() {
+            assert!(node.value.source().ptr_eq(source));
+            assert_eq!(node.value.position().line_offset, line_offset);
+            assert_eq!(node.value.position().byte_offset, node.range.start);
+        }
+    }
+
+    #[test]
+    fn rejects_edits_that_dont_fit_the_source() {
+        let document = Document::parse("é\n", &line()).unwrap();
+        for range in [1..2, Range { start: 2, end: 1 }, 2..4] {
+            let edit = TextEdit::new(range, "x");
+            assert!(matches!(document.reparse(&edit, &line()), Err(ReparseError::InvalidEdit(x)) if x == edit));
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{advance, ColumnMode, PositionIndex};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// LINE INDEX
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Where each line of a document starts, for converting between offsets and
+/// line/column positions in `O(log n)` plus a scan of a few graphemes.
+///
+/// Lines end at `\n`, `\r\n` or a lone `\r`. Columns count graphemes from the
+/// start of the line unless a `ColumnMode` says otherwise. The index only
+/// stores offsets, so methods that look at characters take the `source` it
+/// was built from.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    lines: Vec<LineStart>,
+    /// Every line start, plus a grapheme boundary every `CHECKPOINT_INTERVAL`
+    /// graphemes within a line, so a long line isn't scanned from its start.
+    checkpoints: Vec<Checkpoint>,
+    /// One past the last character, in every unit.
+    end: LineStart,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LineStart {
+    byte_offset: usize,
+    scalar_offset: usize,
+    grapheme_offset: usize,
+    utf16_offset: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Checkpoint {
+    offsets: LineStart,
+    line_offset: usize,
+    column_offset: usize,
+}
+
+const CHECKPOINT_INTERVAL: usize = 64;
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let mut current = LineStart { byte_offset: 0, scalar_offset: 0, grapheme_offset: 0, utf16_offset: 0 };
+        let mut lines = vec![current];
+        let mut checkpoints = vec![Checkpoint { offsets: current, line_offset: 0, column_offset: 0 }];
+        let mut column_offset = 0;
+        for (byte_offset, grapheme) in source.grapheme_indices(true) {
+            current = LineStart {
+                byte_offset: byte_offset + grapheme.len(),
+                scalar_offset: current.scalar_offset + grapheme.chars().count(),
+                grapheme_offset: current.grapheme_offset + 1,
+                utf16_offset: current.utf16_offset + grapheme.encode_utf16().count(),
+            };
+            column_offset += 1;
+            if is_line_break(grapheme) {
+                lines.push(current);
+                column_offset = 0;
+            }
+            if column_offset % CHECKPOINT_INTERVAL == 0 {
+                checkpoints.push(Checkpoint { offsets: current, line_offset: lines.len() - 1, column_offset });
+            }
+        }
+        Self { lines, checkpoints, end: current }
+    }
+    /// Always at least one; a trailing line break starts an empty last line.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+    /// The line containing `byte_offset`.
+    pub fn line_of_byte(&self, byte_offset: usize) -> usize {
+        self.lines.partition_point(|x| x.byte_offset <= byte_offset) - 1
+    }
+    /// The bytes of `line`, including its line break.
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = self.lines.get(line)?.byte_offset;
+        let end = self.lines
+            .get(line + 1)
+            .map(|x| x.byte_offset)
+            .unwrap_or(self.end.byte_offset);
+        Some(start..end)
+    }
+    /// The text of `line` without its line break.
+    pub fn line_text<'a>(&self, source: &'a str, line: usize) -> Option<&'a str> {
+        let text = source.get(self.line_range(line)?)?;
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+    /// The position of `byte_offset`, which must be on a char boundary.
+    ///
+    /// A char inside a multi-char grapheme shares the column of the
+    /// grapheme's first char.
+    pub fn position_of_byte(&self, source: &str, byte_offset: usize) -> PositionIndex {
+        let checkpoint = self.checkpoint_before(|x| x.byte_offset <= byte_offset);
+        let start = checkpoint.offsets.byte_offset;
+        let leading = &source[start..byte_offset];
+        let graphemes = source[start..]
+            .grapheme_indices(true)
+            .take_while(|(start, grapheme)| start + grapheme.len() <= leading.len())
+            .count();
+        PositionIndex {
+            grapheme_offset: checkpoint.offsets.grapheme_offset + graphemes,
+            scalar_offset: checkpoint.offsets.scalar_offset + leading.chars().count(),
+            byte_offset,
+            utf16_offset: checkpoint.offsets.utf16_offset + leading.encode_utf16().count(),
+            line_offset: checkpoint.line_offset,
+            column_offset: checkpoint.column_offset + graphemes,
+        }
+    }
+    /// The position of the `scalar_offset`th char, or `None` past the end.
+    pub fn position_of_scalar(&self, source: &str, scalar_offset: usize) -> Option<PositionIndex> {
+        if scalar_offset > self.end.scalar_offset {
+            return None
+        }
+        let start = self.checkpoint_before(|x| x.scalar_offset <= scalar_offset).offsets;
+        let byte_offset = source[start.byte_offset..]
+            .char_indices()
+            .map(|(x, _)| start.byte_offset + x)
+            .chain([self.end.byte_offset])
+            .nth(scalar_offset - start.scalar_offset)?;
+        Some(self.position_of_byte(source, byte_offset))
+    }
+    /// The position of the `grapheme_offset`th grapheme, or `None` past the end.
+    pub fn position_of_grapheme(&self, source: &str, grapheme_offset: usize) -> Option<PositionIndex> {
+        if grapheme_offset > self.end.grapheme_offset {
+            return None
+        }
+        let start = self.checkpoint_before(|x| x.grapheme_offset <= grapheme_offset).offsets;
+        let byte_offset = source[start.byte_offset..]
+            .grapheme_indices(true)
+            .map(|(x, _)| start.byte_offset + x)
+            .chain([self.end.byte_offset])
+            .nth(grapheme_offset - start.grapheme_offset)?;
+        Some(self.position_of_byte(source, byte_offset))
+    }
+    /// The position of the `utf16_offset`th UTF-16 code unit, or `None` past
+    /// the end or inside a surrogate pair.
+    pub fn position_of_utf16(&self, source: &str, utf16_offset: usize) -> Option<PositionIndex> {
+        if utf16_offset > self.end.utf16_offset {
+            return None
+        }
+        let start = self.checkpoint_before(|x| x.utf16_offset <= utf16_offset).offsets;
+        let mut current = start.utf16_offset;
+        let byte_offset = source[start.byte_offset..]
+            .char_indices()
+            .map(|(x, value)| {
+                let boundary = (start.byte_offset + x, current);
+                current += value.len_utf16();
+                boundary
+            })
+            .chain([(self.end.byte_offset, self.end.utf16_offset)])
+            .take_while(|(_, x)| *x <= utf16_offset)
+            .find(|(_, x)| *x == utf16_offset)?
+            .0;
+        Some(self.position_of_byte(source, byte_offset))
+    }
+    /// The position at `column` of `line`, or `None` if the line is shorter.
+    ///
+    /// The column just past the last grapheme (where the line break starts)
+    /// is allowed.
+    pub fn position_of_line_column(&self, source: &str, line: usize, column: usize) -> Option<PositionIndex> {
+        self.position_of_line_column_in(source, line, column, ColumnMode::Graphemes)
+    }
+    /// Like `position_of_line_column` with `column` counted in `mode`.
+    ///
+    /// A display column inside a wide grapheme or a tab resolves to its start;
+    /// in the other modes a column inside a grapheme or surrogate pair is `None`.
+    pub fn position_of_line_column_in(
+        &self,
+        source: &str,
+        line: usize,
+        column: usize,
+        mode: ColumnMode,
+    ) -> Option<PositionIndex> {
+        let text = self.line_text(source, line)?;
+        let start = self.lines[line].byte_offset;
+        let mut boundaries = column_boundaries(text, mode);
+        boundaries.retain(|(_, x)| *x <= column);
+        let (byte_offset, found) = boundaries.pop()?;
+        let exact = found == column;
+        let is_last = byte_offset == text.len();
+        if !exact && (is_last || !matches!(mode, ColumnMode::DisplayWidth { .. })) {
+            return None
+        }
+        Some(self.position_of_byte(source, start + byte_offset))
+    }
+    /// The column of `byte_offset` within its line, counted in `mode`.
+    ///
+    /// Like `column_offset`, a char inside a multi-char grapheme is at the
+    /// grapheme's column in the grapheme based modes.
+    pub fn column_of_byte(&self, source: &str, byte_offset: usize, mode: ColumnMode) -> usize {
+        let start = self.lines[self.line_of_byte(byte_offset)].byte_offset;
+        let leading = &source[start..byte_offset];
+        let leading = match mode {
+            ColumnMode::Graphemes | ColumnMode::DisplayWidth { .. } => {
+                let end = source[start..]
+                    .grapheme_indices(true)
+                    .map(|(x, grapheme)| x + grapheme.len())
+                    .take_while(|x| *x <= leading.len())
+                    .last()
+                    .unwrap_or(0);
+                &leading[..end]
+            }
+            ColumnMode::Chars | ColumnMode::Utf16 => leading,
+        };
+        mode.measure(leading)
+    }
+    /// The last checkpoint at or before an offset, given `is_before` for that
+    /// offset's unit.
+    fn checkpoint_before(&self, is_before: impl Fn(&LineStart) -> bool) -> Checkpoint {
+        self.checkpoints[self.checkpoints.partition_point(|x| is_before(&x.offsets)) - 1]
+    }
+}
+
+/// Every offset in `line` a column can point at, with its column in `mode`.
+fn column_boundaries(line: &str, mode: ColumnMode) -> Vec<(usize, usize)> {
+    let mut column = 0;
+    let mut boundaries = Vec::new();
+    match mode {
+        ColumnMode::Chars | ColumnMode::Utf16 => {
+            for (offset, value) in line.char_indices() {
+                boundaries.push((offset, column));
+                column += match mode {
+                    ColumnMode::Utf16 => value.len_utf16(),
+                    _ => 1,
+                };
+            }
+        }
+        ColumnMode::Graphemes | ColumnMode::DisplayWidth { .. } => {
+            for (offset, grapheme) in line.grapheme_indices(true) {
+                boundaries.push((offset, column));
+                column = match mode {
+                    ColumnMode::DisplayWidth { tab_width } => advance(column, grapheme, tab_width),
+                    _ => column + 1,
+                };
+            }
+        }
+    }
+    boundaries.push((line.len(), column));
+    boundaries
+}
+
+fn is_line_break(grapheme: &str) -> bool {
+    matches!(grapheme, "\n" | "\r\n" | "\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_end_at_each_kind_of_line_break() {
+        let source = "a\nb\r\nc\rd\n";
+        let index = LineIndex::new(source);
+        assert_eq!(index.line_count(), 5);
+        let lines = (0..5).map(|x| index.line_text(source, x).unwrap()).collect::<Vec<_>>();
+        assert_eq!(lines, ["a", "b", "c", "d", ""]);
+        assert_eq!(index.line_range(1), Some(2..5));
+        assert_eq!(index.line_range(5), None);
+        assert_eq!(index.line_of_byte(3), 1);
+        assert_eq!(index.line_of_byte(4), 1);
+        assert_eq!(index.line_of_byte(source.len()), 4);
+    }
+
+    #[test]
+    fn a_char_inside_a_grapheme_shares_its_column() {
+        let source = "e\u{301}x";
+        let index = LineIndex::new(source);
+        let inside = index.position_of_byte(source, 1);
+        assert_eq!((inside.column_offset, inside.grapheme_offset, inside.scalar_offset), (0, 0, 1));
+        let after = index.position_of_byte(source, 3);
+        assert_eq!((after.column_offset, after.grapheme_offset, after.scalar_offset), (1, 1, 2));
+    }
+
+    #[test]
+    fn offsets_round_trip_through_every_unit() {
+        let source = "a😀\r\nbe\u{301}\rz\n";
+        let index = LineIndex::new(source);
+        for (byte_offset, _) in source.char_indices().chain([(source.len(), ' ')]) {
+            let position = index.position_of_byte(source, byte_offset);
+            assert_eq!(position.byte_offset, byte_offset);
+            assert_eq!(index.position_of_scalar(source, position.scalar_offset), Some(position));
+            assert_eq!(index.position_of_utf16(source, position.utf16_offset), Some(position));
+        }
+        let boundaries = source.grapheme_indices(true).map(|(x, _)| x).chain([source.len()]);
+        for byte_offset in boundaries {
+            let position = index.position_of_byte(source, byte_offset);
+            assert_eq!(index.position_of_grapheme(source, position.grapheme_offset), Some(position));
+            let line_column = index.position_of_line_column(source, position.line_offset, position.column_offset);
+            assert_eq!(line_column, Some(position));
+        }
+    }
+
+    #[test]
+    fn offsets_past_the_end_or_inside_a_surrogate_pair_are_none() {
+        let source = "a😀\nb";
+        let index = LineIndex::new(source);
+        assert_eq!(index.position_of_utf16(source, 2), None);
+        assert_eq!(index.position_of_utf16(source, 3).map(|x| x.byte_offset), Some(5));
+        assert_eq!(index.position_of_utf16(source, 6), None);
+        assert_eq!(index.position_of_scalar(source, 5), None);
+        assert_eq!(index.position_of_grapheme(source, 5), None);
+        assert_eq!(index.position_of_line_column(source, 0, 3), None);
+        assert_eq!(index.position_of_line_column(source, 2, 0), None);
+    }
+
+    #[test]
+    fn columns_are_counted_in_each_mode() {
+        let source = "x\n\t😀e\u{301}y";
+        let index = LineIndex::new(source);
+        let y = source.len() - 1;
+        assert_eq!(index.column_of_byte(source, y, ColumnMode::Graphemes), 3);
+        assert_eq!(index.column_of_byte(source, y, ColumnMode::Chars), 4);
+        assert_eq!(index.column_of_byte(source, y, ColumnMode::Utf16), 5);
+        assert_eq!(index.column_of_byte(source, y, ColumnMode::DisplayWidth { tab_width: 4 }), 7);
+        let wide = index.position_of_line_column_in(source, 1, 5, ColumnMode::DisplayWidth { tab_width: 4 });
+        assert_eq!(wide.map(|x| x.byte_offset), Some(3));
+        assert_eq!(index.position_of_line_column_in(source, 1, 2, ColumnMode::Utf16), None);
+    }
+
+    #[test]
+    fn positions_on_a_long_line_match_a_scan_from_its_start() {
+        let source = format!("x\n{}", "ab\u{301}ü😀".repeat(100));
+        let index = LineIndex::new(&source);
+        for (column, (byte_offset, grapheme)) in source[2..].grapheme_indices(true).enumerate() {
+            let position = index.position_of_byte(&source, 2 + byte_offset);
+            assert_eq!((position.line_offset, position.column_offset), (1, column));
+            assert_eq!(position.grapheme_offset, 2 + column);
+            assert_eq!(position.scalar_offset, source[..2 + byte_offset].chars().count());
+            assert_eq!(position.utf16_offset, source[..2 + byte_offset].encode_utf16().count());
+            assert_eq!(index.position_of_grapheme(&source, 2 + column), Some(position));
+            if grapheme.chars().count() > 1 {
+                let inside = index.position_of_byte(&source, 2 + byte_offset + 1);
+                assert_eq!(inside.column_offset, column);
+            }
+        }
+    }
+}
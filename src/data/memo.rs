@@ -0,0 +1,122 @@
+use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc, sync::atomic::{AtomicUsize, Ordering}};
+
+use super::{Output, ParseError, State};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PARSER IDENTITY
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+static NEXT_PARSER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A process-wide unique id for a memoized parser.
+pub(crate) fn fresh_parser_id() -> usize {
+    NEXT_PARSER_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct MemoKey {
+    pub parser: usize,
+    pub offset: usize,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// MEMO TABLE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Results of memoized parsers, shared by every `State` of one evaluation.
+///
+/// Unlike the rest of `State` this is not rolled back when backtracking; a
+/// parser's result at a given offset doesn't depend on how we got there.
+#[derive(Clone, Default)]
+pub struct MemoTable {
+    inner: Rc<RefCell<MemoTableInner>>,
+}
+
+#[derive(Default)]
+struct MemoTableInner {
+    entries: HashMap<MemoKey, Rc<dyn Any>>,
+    /// Seeds of `Parser::left_recursive` still being grown; kept out of
+    /// `entries` so reaching `capacity` can't cut their growth short.
+    pinned: HashMap<MemoKey, Rc<dyn Any>>,
+    /// Once reached, the table is cleared before the next insert.
+    capacity: Option<usize>,
+}
+
+impl MemoTable {
+    pub fn new(capacity: Option<usize>) -> Self {
+        let inner = MemoTableInner { entries: HashMap::default(), pinned: HashMap::default(), capacity };
+        Self { inner: Rc::new(RefCell::new(inner)) }
+    }
+    pub fn len(&self) -> usize {
+        self.inner.borrow().entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn clear(&self) {
+        self.inner.borrow_mut().entries.clear();
+    }
+    pub(crate) fn get<T: Clone + 'static>(&self, key: MemoKey) -> Option<Output<T>> {
+        let inner = self.inner.borrow();
+        inner.pinned
+            .get(&key)
+            .or_else(|| inner.entries.get(&key))
+            .and_then(|x| x.downcast_ref::<Output<T>>())
+            .cloned()
+    }
+    /// Insert an entry that survives clearing until it's `unpin`ned.
+    pub(crate) fn pin<T: 'static>(&self, key: MemoKey, output: Output<T>) {
+        self.inner.borrow_mut().pinned.insert(key, Rc::new(output));
+    }
+    pub(crate) fn unpin(&self, key: MemoKey) {
+        self.inner.borrow_mut().pinned.remove(&key);
+    }
+    pub(crate) fn insert<T: 'static>(&self, key: MemoKey, output: Output<T>) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.capacity.is_some_and(|capacity| inner.entries.len() >= capacity) {
+            inner.entries.clear();
+        }
+        inner.entries.insert(key, Rc::new(output));
+    }
+}
+
+impl<T> Output<T> {
+    /// The entry to store for an output of a parser run for `caller`: an
+    /// error keeps only the context pushed inside the parser, since `replay`
+    /// puts it under whichever caller comes next.
+    pub(crate) fn detach(self, caller: &State) -> Self {
+        let detach = |mut error: ParseError| {
+            let outer = caller.context.iter().cloned().collect::<Vec<_>>();
+            if error.context.ends_with(&outer) {
+                error.context.truncate(error.context.len() - outer.len());
+            }
+            error
+        };
+        match self {
+            Output::Ok { value, state } => Output::Ok { value, state },
+            Output::Fail { state, error } => Output::Fail { state, error: detach(error) },
+            Output::Error { state, error } => Output::Error { state, error: detach(error) },
+        }
+    }
+    /// Replay a memoized output for `caller`: only the input position comes
+    /// from the cached state, the rest (user state, recovered errors, context
+    /// stack) is the caller's, and an error's context ends in the caller's.
+    pub(crate) fn replay(self, caller: &State) -> Self {
+        let resume = |state: State| State { text: state.text, bytes: state.bytes, ..caller.clone() };
+        let attach = |mut error: ParseError| {
+            error.context.extend(caller.context.iter().cloned());
+            error
+        };
+        match self {
+            Output::Ok { value, state } => resume(state).ok(value),
+            Output::Fail { state, error } => resume(state).fail_with(attach(error)),
+            Output::Error { state, error } => resume(state).cut(attach(error)),
+        }
+    }
+}
+
+impl std::fmt::Debug for MemoTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f   .debug_struct("MemoTable")
+            .field("entries", &self.len())
+            .finish()
+    }
+}
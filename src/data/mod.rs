@@ -4,10 +4,34 @@ mod state;
 mod parser;
 mod either;
 mod unit;
+mod error;
+mod span;
+mod user_state;
+mod memo;
+mod source;
+mod line_index;
+mod column;
+mod parallel;
+mod stream;
+mod incremental;
+mod bytes;
+mod trace;
 
 pub use control_flow::*;
 pub use text::*;
 pub use state::*;
 pub use parser::*;
 pub use either::*;
-pub use unit::*;
\ No newline at end of file
+pub use unit::*;
+pub use error::*;
+pub use span::*;
+pub use user_state::*;
+pub use memo::*;
+pub use source::*;
+pub use line_index::*;
+pub use column::*;
+pub use parallel::*;
+pub use stream::*;
+pub use incremental::*;
+pub use bytes::*;
+pub use trace::*;
\ No newline at end of file
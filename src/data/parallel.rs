@@ -0,0 +1,137 @@
+use std::{num::NonZeroUsize, sync::atomic::{AtomicUsize, Ordering}};
+
+use super::{EvaluateSettings, ParseError, Parser};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// SETTINGS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Options for [`Parser::evaluate_parallel`].
+#[derive(Debug, Clone, Default)]
+pub struct ParallelSettings {
+    threads: Option<usize>,
+    memo_capacity: Option<usize>,
+    complete: bool,
+}
+
+impl ParallelSettings {
+    /// Number of worker threads; defaults to the available parallelism.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+    /// See `EvaluateSettings::memo_capacity`; applies to each document.
+    pub fn memo_capacity(mut self, capacity: usize) -> Self {
+        self.memo_capacity = Some(capacity);
+        self
+    }
+    /// Treat trailing input as an error, like `Parser::evaluate_complete`.
+    pub fn complete(mut self, flag: bool) -> Self {
+        self.complete = flag;
+        self
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// OUTPUT
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone)]
+pub struct DocumentOutput<N, T> {
+    pub name: N,
+    pub result: Result<T, ParseError>,
+    /// See `State::recovered_errors`.
+    pub recovered: Vec<ParseError>,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// EVALUATION
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl<T: Send> Parser<T> {
+    /// Parse many `(name, source)` documents on a pool of worker threads,
+    /// returning their outputs in input order.
+    ///
+    /// Each worker builds its own parser with `grammar`, so nothing about the
+    /// grammar is shared between threads. A panic in a worker is re-raised
+    /// here once the others finish.
+    pub fn evaluate_parallel<N, S>(
+        documents: impl IntoIterator<Item = (N, S)>,
+        grammar: impl Fn() -> Self + Sync,
+        settings: ParallelSettings,
+    ) -> Vec<DocumentOutput<N, T>> where S: AsRef<str> + Sync {
+        let (names, sources): (Vec<N>, Vec<S>) = documents.into_iter().unzip();
+        let threads = settings.threads
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, NonZeroUsize::get))
+            .clamp(1, sources.len().max(1));
+        let next = AtomicUsize::new(0);
+        let mut outputs = Vec::<Option<(Result<T, ParseError>, Vec<ParseError>)>>::new();
+        outputs.resize_with(sources.len(), || None);
+        std::thread::scope(|scope| {
+            let workers = (0..threads)
+                .map(|_| scope.spawn(|| {
+                    let parser = grammar();
+                    let mut finished = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(source) = sources.get(index) else {
+                            break
+                        };
+                        let evaluate_settings = match settings.memo_capacity {
+                            Some(capacity) => EvaluateSettings::default().memo_capacity(capacity),
+                            None => EvaluateSettings::default(),
+                        };
+                        let (result, state) = match settings.complete {
+                            true => Self::evaluate_complete_with(source, parser.share(), evaluate_settings),
+                            false => Self::evaluate_with(source, parser.share(), evaluate_settings),
+                        };
+                        finished.push((index, (result, state.recovered_errors())));
+                    }
+                    finished
+                }))
+                .collect::<Vec<_>>();
+            for worker in workers {
+                match worker.join() {
+                    Ok(finished) => {
+                        for (index, output) in finished {
+                            outputs[index] = Some(output);
+                        }
+                    }
+                    Err(panic) => std::panic::resume_unwind(panic),
+                }
+            }
+        });
+        names
+            .into_iter()
+            .zip(outputs)
+            .map(|(name, output)| {
+                let (result, recovered) = output.expect("every document is claimed by a worker");
+                DocumentOutput { name, result, recovered }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CharParser;
+
+    fn digits() -> Parser<usize> {
+        CharParser::char_if(|x| x.is_ascii_digit()).many().map(|x| x.len())
+    }
+
+    #[test]
+    fn outputs_come_back_in_input_order() {
+        let documents = (0..20).map(|x| (x, "1".repeat(x)));
+        let outputs = Parser::evaluate_parallel(documents, digits, ParallelSettings::default().threads(4));
+        for (index, output) in outputs.into_iter().enumerate() {
+            assert_eq!((output.name, output.result.unwrap()), (index, index));
+        }
+    }
+
+    #[test]
+    fn complete_rejects_trailing_input() {
+        let documents = [("a", "12"), ("b", "1x")];
+        let outputs = Parser::evaluate_parallel(documents, digits, ParallelSettings::default().complete(true));
+        assert!(outputs[0].result.is_ok());
+        assert_eq!(outputs[1].result.as_ref().unwrap_err().position.byte_offset, 1);
+    }
+}
@@ -1,36 +1,145 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use colored::Colorize;
 use tree_formatter::{PrettyTree, PrettyTreePrinter, ToPrettyTree};
-use crate::{combinators::SequenceSettings, data::{ControlFlow, FatChar, State, Text}};
+use crate::{combinators::SequenceSettings, data::{Bytes, ControlFlow, Expected, FatChar, MemoTable, ParseError, State, Text, Tracer}};
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A compiled parser; `Send + Sync`, so one grammar can be built once (even in
+/// a `static`) and shared by threads that each run their own evaluations.
 #[derive(Clone)]
 pub struct Parser<A> {
-    pub(crate) binder: Rc<dyn Fn(State) -> Output<A>>
+    pub(crate) binder: Arc<dyn Fn(State) -> Output<A> + Send + Sync>
 }
 
+// - PARSERS ARE SHAREABLE WHATEVER THEY PRODUCE -
+const _: () = {
+    fn assert_send_sync<T: Send + Sync>() {}
+    fn check<A>() {
+        assert_send_sync::<Parser<A>>()
+    }
+};
+
 pub type TextParser = Parser<Text>;
 pub type CharParser = Parser<FatChar>;
+pub type ByteParser = Parser<u8>;
+pub type BytesParser = Parser<Bytes>;
 pub type TupleParser<A, B> = Parser<(A, B)>;
 pub type TripleParser<A, B, C> = Parser<(A, B, C)>;
 pub type QuadrupleParser<A, B, C, D> = Parser<(A, B, C, D)>;
 pub type ControlFlowParser = Parser<ControlFlow>;
 pub type VecParser<A> = Parser<Vec<A>>;
 
+/// Per-call options for [`Parser::evaluate_with`].
+#[derive(Default)]
+pub struct EvaluateSettings {
+    memo_capacity: Option<usize>,
+    tracer: Tracer,
+}
+
+impl EvaluateSettings {
+    /// Bound the memo table used by `Parser::memoize`; it's cleared whenever it fills up.
+    pub fn memo_capacity(mut self, capacity: usize) -> Self {
+        self.memo_capacity = Some(capacity);
+        self
+    }
+    /// Record `Parser::trace` events into `tracer`; nothing is recorded by default.
+    pub fn tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = tracer;
+        self
+    }
+}
+
 impl<T> Parser<T> {
-    pub fn evaluate(source: impl AsRef<str>, parser: Self) -> (Option<T>, State) {
+    pub fn evaluate(source: impl AsRef<str>, parser: Self) -> (Result<T, ParseError>, State) {
+        Self::evaluate_with(source, parser, EvaluateSettings::default())
+    }
+    pub fn evaluate_with(
+        source: impl AsRef<str>,
+        parser: Self,
+        settings: EvaluateSettings,
+    ) -> (Result<T, ParseError>, State) {
+        Self::run(State::initialize_from(source), parser, settings)
+    }
+    /// Like `evaluate`, but trailing input is an error located at the first
+    /// unconsumed character.
+    pub fn evaluate_complete(source: impl AsRef<str>, parser: Self) -> (Result<T, ParseError>, State) {
+        Self::evaluate_complete_with(source, parser, EvaluateSettings::default())
+    }
+    pub fn evaluate_complete_with(
+        source: impl AsRef<str>,
+        parser: Self,
+        settings: EvaluateSettings,
+    ) -> (Result<T, ParseError>, State) {
+        Self::complete(Self::evaluate_with(source, parser, settings))
+    }
+    /// Evaluate over binary input, for parsers built from `ByteParser` and
+    /// `BytesParser`; character parsers see no input.
+    pub fn evaluate_bytes(source: impl Into<Arc<[u8]>>, parser: Self) -> (Result<T, ParseError>, State) {
+        Self::evaluate_bytes_with(source, parser, EvaluateSettings::default())
+    }
+    pub fn evaluate_bytes_with(
+        source: impl Into<Arc<[u8]>>,
+        parser: Self,
+        settings: EvaluateSettings,
+    ) -> (Result<T, ParseError>, State) {
+        Self::run(State::from_bytes(Bytes::new(source)), parser, settings)
+    }
+    /// Parse all of `source` as binary input, treating trailing bytes as an error.
+    pub fn parse_bytes(&self, source: &[u8]) -> Result<T, ParseError> {
+        let output = Self::evaluate_bytes(source, self.share());
+        Self::complete(output).0
+    }
+    /// Parse all of `source`, treating trailing input as an error.
+    pub fn parse(&self, source: &str) -> Result<T, ParseError> {
+        Self::evaluate_complete(source, self.share()).0
+    }
+    /// Parse a prefix of `source`, returning the byte offset where the
+    /// remaining input starts, i.e. the rest is `&source[offset..]`.
+    pub fn parse_partial(&self, source: &str) -> Result<(T, usize), ParseError> {
+        let (result, state) = Self::evaluate(source, self.share());
+        result.map(|value| (value, state.offset()))
+    }
+    /// Evaluate with tracing enabled and return the trace alongside the result.
+    ///
+    /// Every `trace`, `label` and `context` parser tried shows up in the tree
+    /// with the input it saw and what it consumed or why it failed.
+    pub fn evaluate_traced(source: impl AsRef<str>, parser: Self) -> (Result<T, ParseError>, PrettyTree) {
+        let tracer = Tracer::enabled();
+        let settings = EvaluateSettings::default().tracer(tracer.clone());
+        let (result, _) = Self::evaluate_with(source, parser, settings);
+        (result, tracer.to_pretty_tree())
+    }
+    fn run(state: State, parser: Self, settings: EvaluateSettings) -> (Result<T, ParseError>, State) {
         let snippet = State {
-            text: Text::initialize_from(source),
+            memo: MemoTable::new(settings.memo_capacity),
+            tracer: settings.tracer,
+            ..state
         };
         match (parser.binder)(snippet) {
-            Output::Ok { value, state } => (Some(value), state),
-            Output::Fail { state } => (None, state)
+            Output::Ok { value, state } => (Ok(value), state),
+            Output::Fail { state, error } => (Err(error), state),
+            Output::Error { state, error } => (Err(error), state),
+        }
+    }
+    /// Turn a success that left input unconsumed into an error at the first
+    /// unconsumed character or byte.
+    fn complete(output: (Result<T, ParseError>, State)) -> (Result<T, ParseError>, State) {
+        match output {
+            (Ok(_), state) if !state.at_end() => {
+                let error = state.error().expecting(Expected::EndOfInput);
+                (Err(error), state)
+            }
+            output => output,
         }
     }
-    pub(crate) fn init(f: impl Fn(State) -> Output<T> + 'static) -> Self {
-        Self { binder: Rc::new(f) }
+    /// Another handle to the same parser, without needing `T: Clone`.
+    pub(crate) fn share(&self) -> Self {
+        Self { binder: self.binder.clone() }
+    }
+    pub(crate) fn init(f: impl Fn(State) -> Output<T> + Send + Sync + 'static) -> Self {
+        Self { binder: Arc::new(f) }
     }
 }
 
@@ -38,5 +147,47 @@ impl<T> Parser<T> {
 #[derive(Debug, Clone)]
 pub(crate) enum Output<T> {
     Ok { value: T, state: State},
-    Fail { state: State },
+    /// A recoverable failure; alternatives may still be tried.
+    Fail { state: State, error: ParseError },
+    /// A failure after a `Parser::cut`; alternatives are not tried.
+    Error { state: State, error: ParseError },
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{CharParser, Expected, Parser};
+
+    fn letters() -> Parser<String> {
+        CharParser::char_if(char::is_alphabetic).some().map(|x| x.into_iter().map(|x| x.value).collect())
+    }
+
+    #[test]
+    fn parse_requires_all_input() {
+        assert_eq!(letters().parse("ab").unwrap(), "ab");
+        let error = letters().parse("ab1").unwrap_err();
+        assert_eq!((error.position.byte_offset, error.found), (2, Some('1')));
+        assert!(error.expected.contains(&Expected::EndOfInput));
+    }
+
+    #[test]
+    fn parse_partial_returns_where_the_rest_starts() {
+        let source = "éa 1";
+        let (value, offset) = letters().parse_partial(source).unwrap();
+        assert_eq!((value.as_str(), &source[offset..]), ("éa", " 1"));
+        assert!(letters().parse_partial("1").is_err());
+    }
+
+    #[test]
+    fn parse_bytes_requires_all_input() {
+        assert_eq!(Parser::u16_le().parse_bytes(&[1, 0]).unwrap(), 1);
+        let error = Parser::u16_le().parse_bytes(&[1, 0, 2]).unwrap_err();
+        assert_eq!((error.position.byte_offset, error.found_byte), (2, Some(2)));
+    }
+
+    #[test]
+    fn evaluate_complete_keeps_the_final_state() {
+        let (result, state) = Parser::evaluate_complete("ab1", letters());
+        assert!(result.is_err());
+        assert_eq!(state.text.as_str(), "1");
+    }
 }
@@ -0,0 +1,76 @@
+use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+
+use super::{ColumnMode, LineIndex, PositionIndex};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// SOURCE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The input of one evaluation, shared by every `Text` and `FatChar` viewing it.
+///
+/// Positions are worked out on demand from its `LineIndex` rather than
+/// stored per character.
+#[derive(Clone)]
+pub struct Source {
+    inner: Arc<SourceInner>,
+}
+
+struct SourceInner {
+    text: Box<str>,
+    lines: LineIndex,
+    /// One past the furthest byte a parser has looked at; `len() + 1` once
+    /// one has looked for input past the last character.
+    examined: AtomicUsize,
+}
+
+impl Source {
+    pub fn new(text: impl Into<Box<str>>) -> Self {
+        let text = text.into();
+        let lines = LineIndex::new(&text);
+        Self { inner: Arc::new(SourceInner { text, lines, examined: AtomicUsize::new(0) }) }
+    }
+    pub fn as_str(&self) -> &str {
+        &self.inner.text
+    }
+    pub fn len(&self) -> usize {
+        self.inner.text.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.inner.text.is_empty()
+    }
+    pub fn line_index(&self) -> &LineIndex {
+        &self.inner.lines
+    }
+    /// The full position of `byte_offset`, which must be on a char boundary.
+    pub fn position(&self, byte_offset: usize) -> PositionIndex {
+        self.inner.lines.position_of_byte(&self.inner.text, byte_offset)
+    }
+    /// The column of `byte_offset` within its line, counted in `mode`.
+    pub fn column(&self, byte_offset: usize, mode: ColumnMode) -> usize {
+        self.inner.lines.column_of_byte(&self.inner.text, byte_offset, mode)
+    }
+    /// Whether parsing ever needed to know what comes after the last
+    /// character, i.e. whether more input could have changed the outcome.
+    pub(crate) fn end_reached(&self) -> bool {
+        self.inner.examined.load(Ordering::Relaxed) > self.len()
+    }
+    /// Record that parsing depended on the bytes before `until`.
+    pub(crate) fn examine(&self, until: usize) {
+        self.inner.examined.fetch_max(until, Ordering::Relaxed);
+    }
+    /// How far parsing has looked since the last call, as recorded by `examine`.
+    pub(crate) fn take_examined(&self) -> usize {
+        self.inner.examined.swap(0, Ordering::Relaxed)
+    }
+    pub(crate) fn ptr_eq(&self, other: &Source) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl std::fmt::Debug for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f   .debug_struct("Source")
+            .field("bytes", &self.len())
+            .field("lines", &self.inner.lines.line_count())
+            .finish()
+    }
+}
@@ -0,0 +1,125 @@
+use std::ops::Range;
+use tree_formatter::{PrettyTree, ToPrettyTree};
+
+use super::{PositionIndex, Rebase, Relocation};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// SPAN
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A half-open region of the source, `start` inclusive and `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: PositionIndex,
+    pub end: PositionIndex,
+}
+
+impl Span {
+    pub fn new(start: PositionIndex, end: PositionIndex) -> Self {
+        Self { start, end }
+    }
+    pub fn byte_range(&self) -> Range<usize> {
+        self.start.byte_offset..self.end.byte_offset
+    }
+    pub fn char_range(&self) -> Range<usize> {
+        self.start.scalar_offset..self.end.scalar_offset
+    }
+    pub fn utf16_range(&self) -> Range<usize> {
+        self.start.utf16_offset..self.end.utf16_offset
+    }
+    pub fn grapheme_range(&self) -> Range<usize> {
+        self.start.grapheme_offset..self.end.grapheme_offset
+    }
+    /// The lines touched by this span; includes `end`'s line.
+    pub fn line_range(&self) -> Range<usize> {
+        self.start.line_offset..self.end.line_offset + 1
+    }
+    /// Only meaningful for spans on a single line.
+    pub fn column_range(&self) -> Range<usize> {
+        self.start.column_offset..self.end.column_offset
+    }
+    pub fn is_empty(&self) -> bool {
+        self.start.byte_offset == self.end.byte_offset
+    }
+    /// The smallest span covering both `self` and `other`.
+    pub fn join(&self, other: Span) -> Span {
+        let start = match self.start.byte_offset <= other.start.byte_offset {
+            true => self.start,
+            false => other.start,
+        };
+        let end = match self.end.byte_offset >= other.end.byte_offset {
+            true => self.end,
+            false => other.end,
+        };
+        Span { start, end }
+    }
+    /// The source text covered by this span.
+    ///
+    /// Returns `None` if the span doesn't belong to `source`.
+    pub fn slice<'a>(&self, source: &'a str) -> Option<&'a str> {
+        source.get(self.byte_range())
+    }
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}..{}:{}",
+            self.start.line_offset + 1,
+            self.start.column_offset + 1,
+            self.end.line_offset + 1,
+            self.end.column_offset + 1,
+        )
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// SPANNED
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A parsed value along with the region of the source it was parsed from.
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub start: PositionIndex,
+    pub end: PositionIndex,
+}
+
+impl<T> Spanned<T> {
+    pub fn span(&self) -> Span {
+        Span::new(self.start, self.end)
+    }
+    pub fn slice<'a>(&self, source: &'a str) -> Option<&'a str> {
+        self.span().slice(source)
+    }
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned { value: f(self.value), start: self.start, end: self.end }
+    }
+}
+
+/// Positions are assumed to be in the source being relocated from.
+impl<T: Rebase> Rebase for Spanned<T> {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Spanned {
+            value: self.value.rebase(relocation),
+            start: relocation.position(self.start),
+            end: relocation.position(self.end),
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DEBUG
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl ToPrettyTree for Span {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        PrettyTree::value(format!("Span({self})"))
+    }
+}
+impl<T: ToPrettyTree> ToPrettyTree for Spanned<T> {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        PrettyTree::branch_of("Spanned", vec![
+            PrettyTree::key_value("value", &self.value),
+            PrettyTree::key_value("span", self.span()),
+        ])
+    }
+}
@@ -1,28 +1,153 @@
+use itertools::Itertools;
 use tree_formatter::{PrettyTree, ToPrettyTree};
 
-use super::{Output, Text};
+use super::{Bytes, Expected, MemoTable, Output, ParseError, PositionIndex, Text, Tracer, UserState};
 
+/// Names pushed by `Parser::context`, innermost first.
+pub type ContextStack = im_lists::list::List<String>;
+
+/// Errors skipped over by `Parser::recover_with`, newest first.
+pub type RecoveredErrors = im_lists::list::List<ParseError>;
+
+/// The progress of one evaluation. Unlike `Parser` it isn't `Send`; it stays on
+/// the thread running the evaluation.
 #[derive(Debug, Clone)]
 pub struct State {
     pub text: Text,
+    /// The input when evaluating binary input with `Parser::evaluate_bytes`;
+    /// `text` is then empty.
+    pub bytes: Option<Bytes>,
+    pub context: ContextStack,
+    pub user: UserState,
+    pub recovered: RecoveredErrors,
+    /// Shared by the whole evaluation; see `Parser::memoize`.
+    pub memo: MemoTable,
+    /// Shared by the whole evaluation; see `Parser::trace`.
+    pub tracer: Tracer,
+    /// The character before the start of the source, when the input is
+    /// parsed in pieces as by `StreamParser`.
+    pub(crate) preceding: Option<char>,
 }
 
 impl State {
+    pub fn initialize_from(source: impl AsRef<str>) -> Self {
+        Self::from_text(Text::initialize_from(source))
+    }
+    pub(crate) fn from_text(text: Text) -> Self {
+        Self {
+            text,
+            bytes: None,
+            context: ContextStack::new(),
+            user: UserState::default(),
+            recovered: RecoveredErrors::new(),
+            memo: MemoTable::default(),
+            tracer: Tracer::default(),
+            preceding: None,
+        }
+    }
+    pub(crate) fn from_bytes(bytes: Bytes) -> Self {
+        Self { bytes: Some(bytes), ..Self::initialize_from("") }
+    }
+    /// The byte offset reached in whichever input is being parsed.
+    pub(crate) fn offset(&self) -> usize {
+        match &self.bytes {
+            Some(bytes) => bytes.offset(),
+            None => self.text.offset(),
+        }
+    }
+    pub(crate) fn position(&self) -> PositionIndex {
+        match &self.bytes {
+            Some(bytes) => bytes.position(),
+            None => self.text.position(),
+        }
+    }
+    /// Whether all input has been consumed.
+    pub(crate) fn at_end(&self) -> bool {
+        match &self.bytes {
+            Some(bytes) => bytes.is_empty(),
+            None => self.text.is_empty(),
+        }
+    }
+    /// Like `at_end`, also recording that the parse looked for more input.
+    pub(crate) fn examine_end(&self) -> bool {
+        self.text.examine(1);
+        self.at_end()
+    }
+    /// The last character consumed before the current position.
+    pub fn previous_char(&self) -> Option<char> {
+        match self.text.previous() {
+            Some(previous) => Some(previous.value),
+            None => self.preceding,
+        }
+    }
+    /// Every error recovered from so far, in the order they occurred.
+    pub fn recovered_errors(&self) -> Vec<ParseError> {
+        let mut errors = self.recovered.iter().cloned().collect_vec();
+        errors.reverse();
+        errors
+    }
     pub(crate) fn ok<T>(self, value: T) -> Output<T> {
         Output::Ok { value, state: self }
     }
     pub(crate) fn fail<T>(self) -> Output<T> {
-        Output::Fail { state: self }
+        let error = self.error();
+        Output::Fail { state: self, error }
+    }
+    pub(crate) fn fail_expecting<T>(self, item: Expected) -> Output<T> {
+        let error = self.error().expecting(item);
+        Output::Fail { state: self, error }
+    }
+    pub(crate) fn fail_with<T>(self, error: ParseError) -> Output<T> {
+        Output::Fail { state: self, error }
+    }
+    pub(crate) fn cut<T>(self, error: ParseError) -> Output<T> {
+        Output::Error { state: self, error }
+    }
+    /// An error located at the current position with nothing expected yet.
+    pub(crate) fn error(&self) -> ParseError {
+        let mut error = match &self.bytes {
+            Some(bytes) => ParseError { found_byte: bytes.first(), ..ParseError::new(bytes.position(), None) },
+            None => ParseError::new(self.text.position(), self.text.start().map(|x| x.value)),
+        };
+        error.context = self.context.iter().cloned().collect();
+        error
     }
     pub(crate) fn set_text(&self, text: Text) -> Self {
-        Self { text }
+        Self { text, ..self.clone() }
+    }
+    pub(crate) fn set_bytes(&self, bytes: Bytes) -> Self {
+        Self { bytes: Some(bytes), ..self.clone() }
+    }
+    pub(crate) fn set_context(&self, context: ContextStack) -> Self {
+        Self { context, ..self.clone() }
+    }
+    pub(crate) fn set_user(&self, user: UserState) -> Self {
+        Self { user, ..self.clone() }
+    }
+    pub(crate) fn push_recovered(&self, error: ParseError) -> Self {
+        Self { recovered: RecoveredErrors::cons(error, self.recovered.clone()), ..self.clone() }
+    }
+    pub(crate) fn push_context(&self, name: impl Into<String>) -> Self {
+        self.set_context(ContextStack::cons(name.into(), self.context.clone()))
     }
 }
 
 impl ToPrettyTree for State {
     fn to_pretty_tree(&self) -> PrettyTree {
+        let input = match &self.bytes {
+            Some(bytes) => PrettyTree::key_value("bytes", bytes),
+            None => PrettyTree::key_value("text", &self.text),
+        };
         PrettyTree::branch_of("State", vec![
-            PrettyTree::key_value("text", &self.text)
+            input,
+            PrettyTree::key_value("context", PrettyTree::value(format_context(self.context.iter()))),
+            PrettyTree::key_value("user", &self.user),
+            PrettyTree::branch_of("recovered", self.recovered_errors()),
         ])
     }
-}
\ No newline at end of file
+}
+
+/// Renders a context stack (innermost first) as `in A > in B`.
+pub(crate) fn format_context<'a>(context: impl IntoIterator<Item = &'a String>) -> String {
+    context.into_iter().map(|x| format!("in {x}")).join(" > ")
+}
@@ -0,0 +1,328 @@
+use std::io::Read;
+
+use super::{Output, ParseError, Parser, State};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// STREAM PARSER
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Runs `parser` repeatedly over input that arrives in chunks, producing one
+/// item per run without buffering more than the item being parsed.
+///
+/// An item is only produced once its outcome can't change with more input:
+/// if the parser looked past the end of what has been fed so far (to finish
+/// a `many`, check `eof`, or match a token that was cut short) the result is
+/// `Incomplete` until more input is fed or `finish` is called.
+///
+/// Each item is parsed from its own source, so positions in items and errors
+/// are relative to where that item starts; `offset` gives its byte offset in
+/// the whole stream. The character before an item is carried over, so
+/// `preceded_by` and `not_preceded_by` see the same as on the whole input.
+///
+/// Nothing is kept between polls: while an item is `Incomplete`, every `poll`
+/// parses it again from its start. An item fed in `k` chunks, polling after
+/// each, costs `O(k × item length)`, so feed large chunks when items are big.
+pub struct StreamParser<T> {
+    parser: Parser<T>,
+    buffer: String,
+    offset: usize,
+    /// The last character discarded, i.e. the one before the buffered input.
+    previous: Option<char>,
+    finished: bool,
+}
+
+/// The result of `StreamParser::poll`.
+#[derive(Debug, Clone)]
+pub enum StreamOutput<T> {
+    /// An item, parsed from the start of the buffered input.
+    Ready(T),
+    /// The buffered input isn't enough to decide; feed more or `finish`.
+    Incomplete,
+    /// All input was fed and consumed.
+    Finished,
+}
+
+impl<T> StreamParser<T> {
+    pub fn new(parser: Parser<T>) -> Self {
+        Self { parser, buffer: String::new(), offset: 0, previous: None, finished: false }
+    }
+    /// Append `chunk` to the buffered input.
+    ///
+    /// Panics if `finish` was already called.
+    pub fn feed(&mut self, chunk: impl AsRef<str>) {
+        assert!(!self.finished, "fed a finished stream");
+        self.buffer.push_str(chunk.as_ref());
+    }
+    /// Mark the end of input; whatever is buffered is parsed as final.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    /// Input fed but not yet consumed by an item.
+    pub fn buffered(&self) -> &str {
+        &self.buffer
+    }
+    /// The byte offset in the whole stream where the buffered input starts.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+    /// Try to parse the next item from the buffered input.
+    ///
+    /// A failure is only reported once it can't be fixed by more input; the
+    /// buffered input is left as is, so the caller may skip some of it with
+    /// `discard` and carry on.
+    pub fn poll(&mut self) -> Result<StreamOutput<T>, ParseError> {
+        if self.finished && self.buffer.is_empty() {
+            return Ok(StreamOutput::Finished)
+        }
+        let state = State { preceding: self.previous, ..State::initialize_from(&self.buffer) };
+        let source = state.text.source().clone();
+        let output = (self.parser.binder)(state);
+        if source.end_reached() && !self.finished {
+            return Ok(StreamOutput::Incomplete)
+        }
+        match output {
+            Output::Ok { value, state } => {
+                let consumed = state.text.offset();
+                if consumed == 0 {
+                    return Err(state.error())
+                }
+                self.discard(consumed);
+                Ok(StreamOutput::Ready(value))
+            }
+            Output::Fail { error, .. } => Err(error),
+            Output::Error { error, .. } => Err(error),
+        }
+    }
+    /// Drop the first `length` bytes of buffered input, which must end on a
+    /// char boundary.
+    pub fn discard(&mut self, length: usize) {
+        if let Some(last) = self.buffer[..length].chars().next_back() {
+            self.previous = Some(last);
+        }
+        self.buffer.drain(..length);
+        self.offset += length;
+    }
+    /// Parse items from `chunks` (a channel `Receiver<String>` works) until
+    /// they run out, stopping after the first error.
+    pub fn from_chunks<C: AsRef<str>>(
+        parser: Parser<T>,
+        chunks: impl IntoIterator<Item = C>,
+    ) -> impl Iterator<Item = Result<T, StreamError>> {
+        let chunks = chunks.into_iter().map(Ok);
+        Self::new(parser).items(chunks)
+    }
+    /// Parse items from `reader` as it's read, stopping after the first error.
+    ///
+    /// The input must be UTF-8; anything else is an `InvalidData` error.
+    pub fn from_reader(
+        parser: Parser<T>,
+        reader: impl Read,
+    ) -> impl Iterator<Item = Result<T, StreamError>> {
+        Self::new(parser).items(ReaderChunks { reader, pending: Vec::new(), done: false })
+    }
+    fn items<C: AsRef<str>>(
+        mut self,
+        mut chunks: impl Iterator<Item = std::io::Result<C>>,
+    ) -> impl Iterator<Item = Result<T, StreamError>> {
+        let mut stopped = false;
+        std::iter::from_fn(move || {
+            if stopped {
+                return None
+            }
+            loop {
+                let result = match self.poll() {
+                    Ok(StreamOutput::Ready(value)) => Ok(value),
+                    Ok(StreamOutput::Finished) => return None,
+                    Ok(StreamOutput::Incomplete) => {
+                        match chunks.next() {
+                            Some(Ok(chunk)) => self.feed(chunk),
+                            Some(Err(error)) => {
+                                stopped = true;
+                                return Some(Err(StreamError::Io(error)))
+                            }
+                            None => self.finish(),
+                        }
+                        continue
+                    }
+                    Err(error) => {
+                        stopped = true;
+                        Err(StreamError::Parse { offset: self.offset, error })
+                    }
+                };
+                return Some(result)
+            }
+        })
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// READER CHUNKS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Reads UTF-8 text in chunks, holding back a char split across reads.
+struct ReaderChunks<R> {
+    reader: R,
+    pending: Vec<u8>,
+    done: bool,
+}
+
+impl<R: Read> Iterator for ReaderChunks<R> {
+    type Item = std::io::Result<String>;
+    fn next(&mut self) -> Option<Self::Item> {
+        use std::io::{Error, ErrorKind};
+        if self.done {
+            return None
+        }
+        let mut chunk = [0; READ_CHUNK_SIZE];
+        let length = loop {
+            match self.reader.read(&mut chunk) {
+                Ok(length) => break length,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Some(Err(error)),
+            }
+        };
+        if length == 0 {
+            self.done = true;
+            if self.pending.is_empty() {
+                return None
+            }
+            let error = Error::new(ErrorKind::InvalidData, "stream ended inside a UTF-8 sequence");
+            return Some(Err(error))
+        }
+        self.pending.extend_from_slice(&chunk[..length]);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => {
+                self.done = true;
+                return Some(Err(Error::new(ErrorKind::InvalidData, error)))
+            }
+        };
+        let rest = self.pending.split_off(valid);
+        let text = std::mem::replace(&mut self.pending, rest);
+        Some(Ok(String::from_utf8(text).expect("checked above")))
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// STREAM ERROR
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug)]
+pub enum StreamError {
+    Io(std::io::Error),
+    /// `error` is positioned relative to `offset`, the byte offset in the
+    /// stream where the failed item started.
+    Parse { offset: usize, error: ParseError },
+}
+
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{error}"),
+            Self::Parse { offset, error } => write!(f, "{error} (in the item at byte {offset})"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CharParser;
+
+    /// A run of digits followed by `;`.
+    fn record() -> Parser<String> {
+        CharParser::char_if(|x| x.is_ascii_digit())
+            .some()
+            .and_(CharParser::char(';'))
+            .map(|(digits, _)| digits.into_iter().map(|x| x.value).collect())
+    }
+
+    #[test]
+    fn waits_until_an_item_cant_change() {
+        let mut stream = StreamParser::new(record());
+        stream.feed("12");
+        assert!(matches!(stream.poll(), Ok(StreamOutput::Incomplete)));
+        stream.feed("3;4");
+        assert!(matches!(stream.poll(), Ok(StreamOutput::Ready(x)) if x == "123"));
+        assert_eq!((stream.buffered(), stream.offset()), ("4", 4));
+        assert!(matches!(stream.poll(), Ok(StreamOutput::Incomplete)));
+        stream.finish();
+        assert!(stream.poll().is_err());
+        stream.discard(1);
+        assert!(matches!(stream.poll(), Ok(StreamOutput::Finished)));
+    }
+
+    #[test]
+    fn items_see_the_character_before_them() {
+        let parser = CharParser::char('a').or(CharParser::char('b').preceded_by(|x| x == 'a'));
+        let items = StreamParser::from_chunks(parser.map(|x| x.value), ["a", "b"]).collect::<Result<String, _>>();
+        assert_eq!(items.unwrap(), "ab");
+        let mut stream = StreamParser::new(CharParser::char('b').not_preceded_by(|x| x == 'a'));
+        stream.feed("ab");
+        stream.discard(1);
+        assert!(stream.poll().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "fed a finished stream")]
+    fn feeding_a_finished_stream_panics() {
+        let mut stream = StreamParser::new(record());
+        stream.finish();
+        stream.feed("1;");
+    }
+
+    #[test]
+    fn items_may_span_chunks() {
+        let items = StreamParser::from_chunks(record(), ["1", "2;3", "4;", "5;"]);
+        let items = items.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(items, ["12", "34", "5"]);
+    }
+
+    #[test]
+    fn errors_carry_the_offset_of_their_item() {
+        let mut items = StreamParser::from_chunks(record(), ["1;2", "x;"]);
+        assert_eq!(items.next().unwrap().unwrap(), "1");
+        let Some(Err(StreamError::Parse { offset, error })) = items.next() else { panic!("expected an error") };
+        assert_eq!((offset, error.position.byte_offset), (2, 1));
+        assert!(items.next().is_none());
+    }
+
+    #[test]
+    fn a_reader_may_split_a_char_across_reads() {
+        /// Hands out one byte per read.
+        struct Trickle(std::collections::VecDeque<u8>);
+        impl Read for Trickle {
+            fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.pop_front() {
+                    Some(byte) => {
+                        buffer[0] = byte;
+                        Ok(1)
+                    }
+                    None => Ok(0),
+                }
+            }
+        }
+        let word = CharParser::char_if(|x| x != ' ')
+            .some()
+            .and_(CharParser::char(' ').optional())
+            .map(|(chars, _)| chars.into_iter().map(|x| x.value).collect::<String>());
+        let reader = Trickle("héé 😀".bytes().collect());
+        let items = StreamParser::from_reader(word, reader).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(items, ["héé", "😀"]);
+        let invalid = Trickle([b'1', 0xff].into_iter().collect());
+        let mut items = StreamParser::from_reader(record(), invalid);
+        assert!(matches!(items.next(), Some(Err(StreamError::Io(_)))));
+    }
+}
@@ -1,51 +1,99 @@
-use itertools::Itertools;
-use unicode_segmentation::UnicodeSegmentation;
-use std::rc::Rc;
 use tree_formatter::{PrettyTree, ToPrettyTree};
 
-pub type FatCharList = im_lists::list::List<FatChar>;
+use super::{ColumnMode, Rebase, Relocation, Source, Span};
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A view of a byte range of a `Source`; cloning and slicing never copy the text.
 #[derive(Clone)]
 pub struct Text {
-    data: FatCharList
+    source: Source,
+    start: usize,
+    end: usize,
 }
 
 impl Text {
     pub fn initialize_from(source: impl AsRef<str>) -> Self {
-        let source = source.as_ref().to_owned();
-        Self {
-            data: FatCharList::from_iter(to_fat_chars(&source)),
-        }
+        Self::from_source(Source::new(source.as_ref()))
     }
-    pub fn from_iter(list: impl IntoIterator<Item=FatChar>) -> Self {
-        Self {
-            data: list.into_iter().collect()
+    /// The whole of `source`.
+    pub fn from_source(source: Source) -> Self {
+        let end = source.len();
+        Self { source, start: 0, end }
+    }
+    /// The rest of `source` from `byte_offset`, which must be on a char boundary.
+    pub fn from_offset(source: Source, byte_offset: usize) -> Self {
+        let end = source.len();
+        Self { source, start: byte_offset.min(end), end }
+    }
+    /// The text spanned by `chars`, which must be consecutive characters of
+    /// `anchor`'s source starting where `anchor` starts, or `None` if they
+    /// aren't. No characters gives the empty text at `anchor`.
+    pub fn from_chars(anchor: &Text, chars: impl IntoIterator<Item = FatChar>) -> Option<Self> {
+        let mut end = anchor.start;
+        for char in chars {
+            if !char.source.ptr_eq(&anchor.source) || char.byte_offset != end {
+                return None
+            }
+            end += char.value.len_utf8();
         }
+        Some(Self { source: anchor.source.clone(), start: anchor.start, end })
+    }
+    pub fn source(&self) -> &Source {
+        &self.source
     }
+    pub fn as_str(&self) -> &str {
+        &self.source.as_str()[self.start..self.end]
+    }
+    /// The byte offset of the first character in the source.
+    pub fn offset(&self) -> usize {
+        self.start
+    }
+    /// Length in chars.
     pub fn len(&self) -> usize {
-        self.data.len()
+        self.as_str().chars().count()
+    }
+    pub fn byte_len(&self) -> usize {
+        self.end - self.start
     }
-    pub fn start(&self) -> Option<&FatChar> {
-        self.data.first()
+    pub fn start(&self) -> Option<FatChar> {
+        let value = self.as_str().chars().next()?;
+        Some(self.fat_char(self.start, value))
     }
-    pub fn end(&self) -> Option<&FatChar> {
-        self.data.last()
+    pub fn end(&self) -> Option<FatChar> {
+        let value = self.as_str().chars().next_back()?;
+        Some(self.fat_char(self.end - value.len_utf8(), value))
+    }
+    /// The character immediately before `start` in the source, or `None` at
+    /// the start of input.
+    pub fn previous(&self) -> Option<FatChar> {
+        let value = self.source.as_str()[..self.start].chars().next_back()?;
+        Some(self.fat_char(self.start - value.len_utf8(), value))
     }
     pub fn start_index(&self) -> Option<PositionIndex> {
-        self.data.first().map(|x| x.index)
+        self.start().map(|x| x.index())
     }
     pub fn end_index(&self) -> Option<PositionIndex> {
-        self.data.last().map(|x| x.index)
+        self.end().map(|x| x.index())
+    }
+    /// The position of the first character, or the end of input position if empty.
+    pub fn position(&self) -> PositionIndex {
+        self.source.position(self.start)
+    }
+    /// The column of the first character, counted in `mode`.
+    pub fn column(&self, mode: ColumnMode) -> usize {
+        self.source.column(self.start, mode)
+    }
+    /// From the first character up to just past the last.
+    pub fn span(&self) -> Span {
+        Span::new(self.position(), self.source.position(self.end))
     }
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.start == self.end
     }
     pub fn has_prefix(&self, prefix: impl AsRef<str>) -> bool {
         let prefix = prefix.as_ref();
-        let prefix_chars = prefix.chars().collect_vec();
         // - -
         if self.is_empty() && prefix.is_empty() {
             return true
@@ -53,56 +101,44 @@ impl Text {
         if self.is_empty() || prefix.is_empty() {
             return false
         }
-        if self.data.len() < prefix_chars.len() {
-            return false
-        }
-        let is_match = self.data
-            .iter()
-            .zip(prefix_chars)
-            .all(|(l, r)| {
-                l.value == r
-            });
         // - -
-        return is_match
+        self.as_str().starts_with(prefix)
     }
     pub fn pop_prefix(&self, prefix: impl AsRef<str>) -> Option<(Self, Self)> {
         let prefix = prefix.as_ref();
-        let prefix_len = prefix.chars().count();
         if !self.has_prefix(prefix) {
             return None
         }
-        let leading = self.data.take(prefix_len);
-        let trailing = self.data.tail(prefix_len)?;
-        let is_valid = leading
-            .iter()
-            .zip(prefix.chars())
-            .all(|(l, r)| {
-                l.value == r
-            });
-        assert!(is_valid);
-        let leading = self.set_data(leading);
-        let trailing = self.set_data(trailing);
-        Some((leading, trailing))
-    }
-    // pub fn take(&self, count: usize) -> Option<(Self, Self)> {
-    //     if self.data.len() < count {
-    //         return None
-    //     }
-    //     let leading = self.set_data(
-    //         self.data.take(count)
-    //     );
-    //     let trailing = self.set_data(
-    //         self.data.tail(count)?
-    //     );
-    //     Some((leading, trailing))
-    // }
+        Some(self.split_at(self.start + prefix.len()))
+    }
     pub fn uncons(&self) -> Option<(FatChar, Self)> {
-        let mut copy = self.data.clone();
-        let first = copy.pop_front()?;
-        Some((first, self.set_data(copy)))
+        let first = self.start()?;
+        let (_, rest) = self.split_at(self.start + first.value.len_utf8());
+        Some((first, rest))
     }
-    fn set_data(&self, data: FatCharList) -> Self {
-        Self { data }
+    /// The leading part of `self` that comes before its suffix `rest`.
+    pub fn consumed_until(&self, rest: &Text) -> Text {
+        self.split_at(rest.start.clamp(self.start, self.end)).0
+    }
+    /// Up to `count` leading characters as a string.
+    pub fn take_string(&self, count: usize) -> String {
+        self.as_str().chars().take(count).collect()
+    }
+    /// Record that a parser looked at the next `count` bytes, and so ran out
+    /// of input if there are fewer than that before the end of the source.
+    pub(crate) fn examine(&self, count: usize) {
+        self.source.examine(self.start + count.min(self.byte_len()));
+        if count > self.byte_len() && self.end == self.source.len() {
+            self.source.examine(self.end + 1);
+        }
+    }
+    fn split_at(&self, middle: usize) -> (Self, Self) {
+        let leading = Self { source: self.source.clone(), start: self.start, end: middle };
+        let trailing = Self { source: self.source.clone(), start: middle, end: self.end };
+        (leading, trailing)
+    }
+    fn fat_char(&self, byte_offset: usize, value: char) -> FatChar {
+        FatChar { value, source: self.source.clone(), byte_offset }
     }
 }
 
@@ -115,11 +151,7 @@ impl std::fmt::Debug for Text {
 }
 impl std::fmt::Display for Text {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let chars = self.data
-            .iter()
-            .map(|x| x.value)
-            .collect::<String>();
-        write!(f, "{chars}")
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -128,8 +160,26 @@ impl std::fmt::Display for Text {
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 #[derive(Clone)]
 pub struct FatChar {
-    pub index: PositionIndex,
     pub value: char,
+    source: Source,
+    byte_offset: usize,
+}
+
+impl FatChar {
+    /// Where this character starts; computed from the source's line table.
+    pub fn index(&self) -> PositionIndex {
+        self.source.position(self.byte_offset)
+    }
+    pub fn byte_offset(&self) -> usize {
+        self.byte_offset
+    }
+    pub fn span(&self) -> Span {
+        Span::new(self.index(), self.end_index())
+    }
+    /// The position immediately following this character.
+    pub fn end_index(&self) -> PositionIndex {
+        self.source.position(self.byte_offset + self.value.len_utf8())
+    }
 }
 
 impl std::fmt::Debug for FatChar {
@@ -146,11 +196,13 @@ impl std::fmt::Debug for FatChar {
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PositionIndex {
     pub grapheme_offset: usize,
     pub scalar_offset: usize,
     pub byte_offset: usize,
+    /// UTF-16 code units from the start of input.
+    pub utf16_offset: usize,
     pub line_offset: usize,
     pub column_offset: usize,
 }
@@ -160,43 +212,38 @@ impl PositionIndex {
         byte_offset: 0,
         scalar_offset: 0,
         grapheme_offset: 0,
+        utf16_offset: 0,
         line_offset: 0,
         column_offset: 0,
     };
 }
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-// SECTION NAME
+// REBASE
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-fn to_fat_chars(source: impl AsRef<str>) -> Vec<FatChar> {
-    let mut leading_position = PositionIndex::ZERO;
-    source
-        .as_ref()
-        .grapheme_indices(true)
-        .flat_map(|(byte_offset, graphme)| {
-            // - UPDATE LEADING STATE -
-            let mut results: Vec<FatChar> = Vec::new();
-            // - -
-            leading_position.byte_offset = byte_offset;
-            // - -
-            for char in graphme.chars() {
-                results.push(FatChar {
-                    index: leading_position.clone(),
-                    value: char
-                });
-                // - -
-                leading_position.scalar_offset += 1;
-                if char == '\n' {
-                    leading_position.line_offset += 1;
-                    leading_position.column_offset = 0;
-                }
-            }
-            // - -
-            leading_position.grapheme_offset += 1;
-            leading_position.column_offset += 1;
-            results
-        })
-        .collect_vec()
+impl Rebase for Text {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        if !relocation.applies_to(&self.source) {
+            return self.clone()
+        }
+        Self {
+            source: relocation.current().clone(),
+            start: relocation.offset(self.start),
+            end: relocation.offset(self.end),
+        }
+    }
+}
+impl Rebase for FatChar {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        if !relocation.applies_to(&self.source) {
+            return self.clone()
+        }
+        Self {
+            value: self.value,
+            source: relocation.current().clone(),
+            byte_offset: relocation.offset(self.byte_offset),
+        }
+    }
 }
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
@@ -207,8 +254,53 @@ impl ToPrettyTree for FatChar {
         PrettyTree::value(format!("{:?}", self.value))
     }
 }
+impl ToPrettyTree for PositionIndex {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        PrettyTree::branch_of("PositionIndex", vec![
+            PrettyTree::key_value("line_offset", PrettyTree::value(self.line_offset)),
+            PrettyTree::key_value("column_offset", PrettyTree::value(self.column_offset)),
+            PrettyTree::key_value("scalar_offset", PrettyTree::value(self.scalar_offset)),
+        ])
+    }
+}
 impl ToPrettyTree for Text {
     fn to_pretty_tree(&self) -> PrettyTree {
         PrettyTree::value(format!("{:?}", self.to_string()))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Text;
+
+    #[test]
+    fn from_chars_spans_consecutive_chars_from_the_anchor() {
+        let text = Text::initialize_from("héllo");
+        let (_, rest) = text.uncons().unwrap();
+        let chars = rest.as_str().char_indices().take(2).map(|(ix, _)| {
+            Text::from_offset(text.source().clone(), 1 + ix).start().unwrap()
+        });
+        let spanned = Text::from_chars(&rest, chars).unwrap();
+        assert_eq!((spanned.as_str(), spanned.offset()), ("él", 1));
+        let empty = Text::from_chars(&rest, None).unwrap();
+        assert!(empty.is_empty() && empty.source().ptr_eq(text.source()));
+        assert_eq!(empty.position().byte_offset, 1);
+    }
+
+    #[test]
+    fn from_chars_rejects_gaps_and_other_sources() {
+        let text = Text::initialize_from("abc");
+        let skipping = [text.start().unwrap(), text.end().unwrap()];
+        assert!(Text::from_chars(&text, skipping).is_none());
+        let other = Text::initialize_from("abc");
+        assert!(Text::from_chars(&other, text.start()).is_none());
+    }
+
+    #[test]
+    fn accessors_leave_the_source_unexamined() {
+        let text = Text::initialize_from("héllo");
+        assert!(!text.is_empty() && text.has_prefix("hé") && text.uncons().is_some());
+        assert_eq!(text.source().take_examined(), 0);
+        assert_eq!((text.len(), text.byte_len()), (5, 6));
+    }
+}
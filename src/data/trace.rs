@@ -0,0 +1,184 @@
+use std::{cell::RefCell, rc::Rc};
+
+use tree_formatter::{PrettyTree, ToPrettyTree};
+
+use super::{Output, ParseError, Span, State, Text};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// EVENTS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone)]
+pub enum TraceEvent {
+    /// `input` is the remaining text the parser was given.
+    Enter { name: String, input: Text },
+    Success { name: String, span: Span, consumed: Text },
+    Failure { name: String, error: ParseError },
+    /// A failure after a `Parser::cut`.
+    Error { name: String, error: ParseError },
+}
+
+impl TraceEvent {
+    pub(crate) fn exit<T>(name: String, input: &Text, output: &Output<T>) -> Self {
+        match output {
+            Output::Ok { state, .. } => {
+                let span = Span::new(input.position(), state.text.position());
+                let consumed = input.consumed_until(&state.text);
+                TraceEvent::Success { name, span, consumed }
+            }
+            Output::Fail { error, .. } => TraceEvent::Failure { name, error: error.clone() },
+            Output::Error { error, .. } => TraceEvent::Error { name, error: error.clone() },
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// TRACER
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Collects `Parser::trace` events for one evaluation.
+///
+/// The default tracer is disabled and records nothing. Like `MemoTable` it is
+/// shared by every `State` of an evaluation, so keep a clone around to read
+/// the events back afterwards.
+#[derive(Clone, Default)]
+pub struct Tracer {
+    events: Option<Rc<RefCell<Vec<TraceEvent>>>>,
+}
+
+impl Tracer {
+    pub fn enabled() -> Self {
+        Self { events: Some(Rc::default()) }
+    }
+    pub fn disabled() -> Self {
+        Self { events: None }
+    }
+    pub fn is_enabled(&self) -> bool {
+        self.events.is_some()
+    }
+    pub fn events(&self) -> Vec<TraceEvent> {
+        self.events
+            .as_ref()
+            .map(|x| x.borrow().clone())
+            .unwrap_or_default()
+    }
+    pub fn clear(&self) {
+        if let Some(events) = self.events.as_ref() {
+            events.borrow_mut().clear();
+        }
+    }
+    pub(crate) fn record(&self, event: TraceEvent) {
+        if let Some(events) = self.events.as_ref() {
+            events.borrow_mut().push(event);
+        }
+    }
+    /// Run `binder` between an enter and an exit event for `name`.
+    pub(crate) fn run<T>(&self, name: &str, state: State, binder: impl FnOnce(State) -> Output<T>) -> Output<T> {
+        if !self.is_enabled() {
+            return binder(state)
+        }
+        let input = state.text.clone();
+        self.record(TraceEvent::Enter { name: name.to_owned(), input: input.clone() });
+        let output = binder(state);
+        self.record(TraceEvent::exit(name.to_owned(), &input, &output));
+        output
+    }
+}
+
+impl std::fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f   .debug_struct("Tracer")
+            .field("enabled", &self.is_enabled())
+            .field("events", &self.events().len())
+            .finish()
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DEBUG
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// How much of the input or consumed text a trace line shows.
+const PREVIEW_LENGTH: usize = 24;
+
+fn preview(text: &Text) -> String {
+    let mut preview = text.take_string(PREVIEW_LENGTH);
+    if text.as_str().chars().nth(PREVIEW_LENGTH).is_some() {
+        preview.push('…');
+    }
+    format!("{preview:?}")
+}
+
+/// Nests the flat event list into one branch per traced parser.
+impl ToPrettyTree for Tracer {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        struct Frame {
+            label: String,
+            children: Vec<PrettyTree>,
+        }
+        fn close(frame: Frame, outcome: String) -> PrettyTree {
+            let label = format!("{} {outcome}", frame.label);
+            match frame.children.is_empty() {
+                true => PrettyTree::value(label),
+                false => PrettyTree::branch_of(label, frame.children),
+            }
+        }
+        let mut stack = vec![Frame { label: String::from("Trace"), children: Vec::new() }];
+        for event in self.events() {
+            let outcome = match event {
+                TraceEvent::Enter { name, input } => {
+                    let position = input.position();
+                    let label = format!(
+                        "{name} @ {}:{} {}",
+                        position.line_offset + 1,
+                        position.column_offset + 1,
+                        preview(&input),
+                    );
+                    stack.push(Frame { label, children: Vec::new() });
+                    continue
+                }
+                TraceEvent::Success { span, consumed, .. } => format!("✓ {span} {}", preview(&consumed)),
+                TraceEvent::Failure { error, .. } => format!("✗ {}", error.message()),
+                TraceEvent::Error { error, .. } => format!("✗! {}", error.message()),
+            };
+            if stack.len() > 1 {
+                let frame = stack.pop().unwrap();
+                stack.last_mut().unwrap().children.push(close(frame, outcome));
+            }
+        }
+        // - FRAMES LEFT OPEN BY A PANIC OR AN UNFINISHED EVALUATION -
+        while stack.len() > 1 {
+            let frame = stack.pop().unwrap();
+            stack.last_mut().unwrap().children.push(close(frame, String::from("…")));
+        }
+        let root = stack.pop().unwrap();
+        PrettyTree::branch_of(root.label, root.children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{CharParser, EvaluateSettings, Parser};
+
+    #[test]
+    fn records_nested_parsers_in_order() {
+        let tracer = Tracer::enabled();
+        let inner = CharParser::char('b').trace("b");
+        let parser = CharParser::char('a').and_(inner).label("ab");
+        let settings = EvaluateSettings::default().tracer(tracer.clone());
+        let _ = Parser::evaluate_with("ac", parser, settings);
+        let events = tracer.events().into_iter().map(|event| match event {
+            TraceEvent::Enter { name, input } => format!("enter {name} {input}"),
+            TraceEvent::Success { name, consumed, .. } => format!("ok {name} {consumed}"),
+            TraceEvent::Failure { name, error } => format!("fail {name} {}", error.position.byte_offset),
+            TraceEvent::Error { name, .. } => format!("error {name}"),
+        });
+        assert_eq!(events.collect::<Vec<_>>(), ["enter ab ac", "enter b c", "fail b 1", "fail ab 1"]);
+    }
+
+    #[test]
+    fn records_nothing_by_default() {
+        let tracer = Tracer::default();
+        let settings = EvaluateSettings::default().tracer(tracer.clone());
+        let _ = Parser::evaluate_with("a", CharParser::char('a').trace("a"), settings);
+        assert!(tracer.events().is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+use std::{any::{Any, TypeId}, collections::HashMap, rc::Rc};
+use itertools::Itertools;
+use tree_formatter::{PrettyTree, ToPrettyTree};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// USER STATE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Grammar-defined values carried alongside the input, one slot per type.
+///
+/// Updates never mutate in place, so backtracking to an earlier `State` also
+/// restores the user state it had at that point.
+#[derive(Clone, Default)]
+pub struct UserState {
+    entries: Rc<HashMap<TypeId, UserStateEntry>>,
+}
+
+#[derive(Clone)]
+struct UserStateEntry {
+    type_name: &'static str,
+    value: Rc<dyn Any>,
+}
+
+impl UserState {
+    pub fn get<S: Clone + 'static>(&self) -> Option<S> {
+        self.entries
+            .get(&TypeId::of::<S>())
+            .and_then(|x| x.value.downcast_ref::<S>())
+            .cloned()
+    }
+    pub fn insert<S: 'static>(&self, value: S) -> Self {
+        let mut entries = self.entries.clone();
+        let entry = UserStateEntry {
+            type_name: std::any::type_name::<S>(),
+            value: Rc::new(value),
+        };
+        Rc::make_mut(&mut entries).insert(TypeId::of::<S>(), entry);
+        Self { entries }
+    }
+    pub fn remove<S: 'static>(&self) -> Self {
+        if !self.entries.contains_key(&TypeId::of::<S>()) {
+            return self.clone()
+        }
+        let mut entries = self.entries.clone();
+        Rc::make_mut(&mut entries).remove(&TypeId::of::<S>());
+        Self { entries }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    fn type_names(&self) -> Vec<&'static str> {
+        self.entries.values().map(|x| x.type_name).sorted().collect_vec()
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DEBUG
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl std::fmt::Debug for UserState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f   .debug_set()
+            .entries(self.type_names())
+            .finish()
+    }
+}
+impl ToPrettyTree for UserState {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        let names = self.type_names().into_iter().map(PrettyTree::value).collect_vec();
+        if names.is_empty() {
+            return PrettyTree::value("[]")
+        }
+        PrettyTree::branch_of("UserState", names)
+    }
+}
@@ -14,7 +14,7 @@ pub fn dev() {
         //     .until_terminator(ControlFlowParser::terminate_if_ok(is_whitespace));
         // CharParser::next()
         //     .sequence(settings)
-        //     .map(Text::from_iter)
+        //     .map_with_start(|start, x| Text::from_chars(start, x).unwrap())
         // crate::formats::markdown::Inline::some(Default::default())
         // crate::formats::markdown::inline::Emphasis::parser(Default::default())
         // CharParser::next().many_unless_(CharParser::char('*'))
@@ -29,25 +29,32 @@ pub fn dev() {
         // CharParser::next().some_till(crate::thunk!{
         //     TextParser::token("@")
         // })
-        CharParser::char_if(|x| x.is_alphabetic()).sequence(Default::default())
+        CharParser::char_if(|x| x.is_alphabetic())
+            .label("letter")
+            .sequence(Default::default())
+            .trace("letters")
         // InlineSequence::some(Default::default())
         // formats::markdown::inline::Emphasis::parser(Default::default())
         // formats::markdown::Inline::plain_text(Default::default())
         // CharParser::next()
         //     .sequence(Default::default())
-        //     .map(Text::from_iter)
+        //     .map_with_start(|start, x| Text::from_chars(start, x).unwrap())
         //     .map(|x| formats::markdown::inline::PlainText {value: x})
         //     .map(formats::markdown::Inline::PlainText)
     };
-    let (output, state) = Parser::evaluate(source, parser);
-    // if let Some(output) = output {
-    //     header("DONE");
-    //     output.to_pretty_tree().print_pretty_tree();
-    // } else {
-    //     header("ERROR!");
-    // }
-    header("FINAL PARSER STATE");
-    state.to_pretty_tree().print_pretty_tree();
+    let (output, trace) = Parser::evaluate_traced(source, parser);
+    header("TRACE");
+    trace.print_pretty_tree();
+    match output {
+        Ok(output) => {
+            header("DONE");
+            output.to_pretty_tree().print_pretty_tree();
+        }
+        Err(error) => {
+            header("ERROR!");
+            error.to_pretty_tree().print_pretty_tree();
+        }
+    }
 }
 
 pub fn header(value: impl AsRef<str>) {
@@ -58,9 +65,9 @@ pub fn header(value: impl AsRef<str>) {
         let top_border    = format!("╭{}╮", "─".repeat(length + 2));
         let bottom_border = format!("╰{}╯", "─".repeat(length + 2));
         // - -
-        let line1 = format!("{}", top_border).cyan();
+        let line1 = top_border.cyan();
         let line2 = format!("│ {} │", label).cyan();
-        let line3 = format!("{}", bottom_border).cyan();
+        let line3 = bottom_border.cyan();
         // - -
         println!("{line1}");
         println!("{line2}");
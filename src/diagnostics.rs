@@ -0,0 +1,272 @@
+use colored::{ColoredString, Colorize};
+use itertools::Itertools;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::data::{advance, ColumnMode, LineIndex, ParseError, PositionIndex, Span};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// DIAGNOSTIC
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A rustc-style report pointing into the original source.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub file_name: Option<String>,
+    pub labels: Vec<Label>,
+    /// Rendered after the snippet as `= note: ...`.
+    pub notes: Vec<String>,
+    /// The unit of the column in the `-->` location line.
+    pub column_mode: ColumnMode,
+}
+
+/// A highlighted region of the source with an optional message.
+///
+/// Primary labels are underlined with `^`, secondary labels with `-`.
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub start: PositionIndex,
+    /// Exclusive; `None` highlights the single character at `start`.
+    pub end: Option<PositionIndex>,
+    pub message: Option<String>,
+    pub primary: bool,
+}
+
+impl Label {
+    pub fn primary(start: PositionIndex, end: Option<PositionIndex>) -> Self {
+        Self { start, end, message: None, primary: true }
+    }
+    pub fn secondary(start: PositionIndex, end: Option<PositionIndex>) -> Self {
+        Self { start, end, message: None, primary: false }
+    }
+    pub fn primary_span(span: Span) -> Self {
+        Self::primary(span.start, Some(span.end))
+    }
+    pub fn secondary_span(span: Span) -> Self {
+        Self::secondary(span.start, Some(span.end))
+    }
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, message: message.into(), ..Self::empty() }
+    }
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, message: message.into(), ..Self::empty() }
+    }
+    fn empty() -> Self {
+        Self {
+            severity: Severity::Error,
+            message: String::default(),
+            file_name: None,
+            labels: Vec::default(),
+            notes: Vec::default(),
+            column_mode: ColumnMode::default(),
+        }
+    }
+    pub fn from_parse_error(error: &ParseError) -> Self {
+        let diagnostic = Self::error(error.message()).with_label(Label::primary(error.position, None));
+        match error.context_trace() {
+            Some(trace) => diagnostic.with_note(trace),
+            None => diagnostic,
+        }
+    }
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        self
+    }
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+    /// Report the location column in `mode`. Snippets are always aligned by
+    /// display width, using `mode`'s tab width if it has one.
+    pub fn with_column_mode(mut self, mode: ColumnMode) -> Self {
+        self.column_mode = mode;
+        self
+    }
+    /// Render with terminal colors.
+    pub fn render(&self, source: impl AsRef<str>) -> String {
+        self.render_with(source.as_ref(), true)
+    }
+    pub fn render_plain(&self, source: impl AsRef<str>) -> String {
+        self.render_with(source.as_ref(), false)
+    }
+    /// Print the colored rendering to stderr.
+    pub fn emit(&self, source: impl AsRef<str>) {
+        eprintln!("{}", self.render(source));
+    }
+    fn render_with(&self, source: &str, colored: bool) -> String {
+        let lines = LineIndex::new(source);
+        let tab_width = match self.column_mode {
+            ColumnMode::DisplayWidth { tab_width } => tab_width,
+            _ => DEFAULT_TAB_WIDTH,
+        };
+        let display = ColumnMode::DisplayWidth { tab_width };
+        let column = |position: &PositionIndex, mode: ColumnMode| -> usize {
+            match source.is_char_boundary(position.byte_offset) {
+                true => lines.column_of_byte(source, position.byte_offset, mode),
+                false => position.column_offset,
+            }
+        };
+        let labels = self.labels
+            .iter()
+            .sorted_by_key(|x| (x.start.line_offset, x.start.column_offset))
+            .collect_vec();
+        let gutter_width = labels
+            .iter()
+            .map(|x| (x.start.line_offset + 1).to_string().len())
+            .max()
+            .unwrap_or(1);
+        let gutter = " ".repeat(gutter_width);
+        let bar = paint("|", colored, |x| x.blue().bold());
+        let mut output = Vec::<String>::new();
+        // - HEADER -
+        let severity = match self.severity {
+            Severity::Error => paint("error", colored, |x| x.red().bold()),
+            Severity::Warning => paint("warning", colored, |x| x.yellow().bold()),
+        };
+        output.push(format!("{severity}: {}", paint(&self.message, colored, |x| x.bold())));
+        let location = labels
+            .iter()
+            .find(|x| x.primary)
+            .or(labels.first())
+            .map(|x| format!("{}:{}", x.start.line_offset + 1, column(&x.start, self.column_mode) + 1));
+        let location = match (self.file_name.as_ref(), location) {
+            (Some(file_name), Some(location)) => Some(format!("{file_name}:{location}")),
+            (Some(file_name), None) => Some(file_name.clone()),
+            (None, location) => location,
+        };
+        if let Some(location) = location {
+            output.push(format!("{gutter}{} {location}", paint("-->", colored, |x| x.blue().bold())));
+        }
+        // - SNIPPETS -
+        if !labels.is_empty() {
+            output.push(format!("{gutter} {bar}"));
+        }
+        for group in labels.chunk_by(|x, y| x.start.line_offset == y.start.line_offset) {
+            let line_offset = group[0].start.line_offset;
+            let line = lines.line_text(source, line_offset).unwrap_or("");
+            let line_number = paint(format!("{:>gutter_width$}", line_offset + 1), colored, |x| x.blue().bold());
+            let line_width = display.measure(line);
+            output.push(format!("{line_number} {bar} {}", expand_tabs(line, tab_width)));
+            for label in group {
+                let start = column(&label.start, display).min(line_width);
+                let padding = " ".repeat(start);
+                let width = match label.end {
+                    Some(end) if end.line_offset == label.start.line_offset => {
+                        column(&end, display).saturating_sub(start)
+                    }
+                    Some(_) => line_width.saturating_sub(start),
+                    // - THE WIDTH OF THE GRAPHEME AT `start` -
+                    None => {
+                        let line_start = lines.line_range(line_offset).map(|x| x.start).unwrap_or(0);
+                        line.get(label.start.byte_offset.saturating_sub(line_start)..)
+                            .and_then(|rest| rest.graphemes(true).next())
+                            .map(|x| advance(start, x, tab_width) - start)
+                            .unwrap_or(1)
+                    }
+                };
+                let marks = match label.primary {
+                    true => paint("^".repeat(width.max(1)), colored, |x| x.red().bold()),
+                    false => paint("-".repeat(width.max(1)), colored, |x| x.blue().bold()),
+                };
+                let message = match (label.message.as_ref(), label.primary) {
+                    (Some(message), true) => format!(" {}", paint(message, colored, |x| x.red().bold())),
+                    (Some(message), false) => format!(" {}", paint(message, colored, |x| x.blue().bold())),
+                    (None, _) => String::new(),
+                };
+                output.push(format!("{gutter} {bar} {padding}{marks}{message}"));
+            }
+        }
+        if !labels.is_empty() {
+            output.push(format!("{gutter} {bar}"));
+        }
+        // - NOTES -
+        for note in self.notes.iter() {
+            output.push(format!("{gutter} {} {note}", paint("= note:", colored, |x| x.bold())));
+        }
+        output.join("\n")
+    }
+}
+
+const DEFAULT_TAB_WIDTH: usize = 4;
+
+fn expand_tabs(line: &str, tab_width: usize) -> String {
+    let mut column = 0;
+    let mut output = String::new();
+    for grapheme in line.graphemes(true) {
+        let next = advance(column, grapheme, tab_width);
+        match grapheme {
+            "\t" => output.push_str(&" ".repeat(next - column)),
+            _ => output.push_str(grapheme),
+        }
+        column = next;
+    }
+    output
+}
+
+fn paint(text: impl AsRef<str>, colored: bool, style: impl Fn(&str) -> ColoredString) -> String {
+    match colored {
+        true => style(text.as_ref()).to_string(),
+        false => text.as_ref().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{CharParser, Parser};
+
+    fn error_at_d(source: &str) -> ParseError {
+        let parser = CharParser::char_if(|x| x != 'd').many().and_(CharParser::char('x')).context("word");
+        parser.parse(source).unwrap_err()
+    }
+
+    #[test]
+    fn renders_a_parse_error_under_its_display_column() {
+        let source = "ab\n\tc😀d";
+        let rendered = Diagnostic::from_parse_error(&error_at_d(source)).with_file_name("a.txt").render_plain(source);
+        let expected = [
+            "error: expected 'x', found 'd'",
+            " --> a.txt:2:4",
+            "  |",
+            "2 |     c😀d",
+            "  |        ^",
+            "  |",
+            "  = note: in word",
+        ];
+        assert_eq!(rendered, expected.join("\n"));
+    }
+
+    #[test]
+    fn reports_the_location_column_in_the_chosen_mode() {
+        let source = "ab\n\tc😀d";
+        let diagnostic = Diagnostic::from_parse_error(&error_at_d(source)).with_column_mode(ColumnMode::Utf16);
+        assert_eq!(diagnostic.render_plain(source).lines().nth(1), Some(" --> 2:5"));
+    }
+
+    #[test]
+    fn underlines_a_span_label() {
+        let source = "let x = 1;";
+        let start = PositionIndex { byte_offset: 4, column_offset: 4, ..PositionIndex::ZERO };
+        let end = PositionIndex { byte_offset: 9, column_offset: 9, ..PositionIndex::ZERO };
+        let label = Label::secondary_span(Span::new(start, end)).with_message("here");
+        let rendered = Diagnostic::warning("unused").with_label(label).render_plain(source);
+        assert_eq!(rendered.lines().nth(4), Some("  |     ----- here"));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::data::{Rebase, Relocation};
+
+use super::*;
+
+impl<Content> Rebase for InDoubleQuotes<Content> where Content: Rebase {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl<Content> Rebase for InSingleQuotes<Content> where Content: Rebase {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl<Content> Rebase for InSquareBrackets<Content> where Content: Rebase {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            open_delimiter: self.open_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            close_delimiter: self.close_delimiter.rebase(relocation),
+        }
+    }
+}
+impl<Content> Rebase for InRoundBrackets<Content> where Content: Rebase {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            open_delimiter: self.open_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            close_delimiter: self.close_delimiter.rebase(relocation),
+        }
+    }
+}
\ No newline at end of file
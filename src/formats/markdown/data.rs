@@ -41,7 +41,7 @@ impl Inline {
         '`',
     ];
     pub fn reserved_tokens() -> HashSet<char> {
-        HashSet::from_iter(Self::RESERVED_TOKENS.iter().map(|x| *x))
+        HashSet::from_iter(Self::RESERVED_TOKENS.iter().copied())
     }
 }
 
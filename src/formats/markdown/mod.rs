@@ -1,7 +1,9 @@
 mod data;
 mod parsers;
 mod debug;
+mod rebase;
 
 pub use data::*;
 pub use parsers::*;
-pub use debug::*;
\ No newline at end of file
+pub use debug::*;
+pub use rebase::*;
\ No newline at end of file
@@ -2,7 +2,7 @@ use std::fmt::Debug;
 
 use itertools::Itertools;
 
-use crate::{combinators::SequenceSettings, data::{CharParser, Parser, Text, TextParser, TupleParser, VecParser}, formats::{InRoundBrackets, InSquareBrackets}, system::{Lazy, Thunk}};
+use crate::{combinators::SequenceSettings, data::{CharParser, Parser, Text, TextParser, TupleParser, VecParser}, formats::{token, InRoundBrackets, InSquareBrackets}, system::{Lazy, Thunk}};
 
 use super::*;
 
@@ -43,7 +43,7 @@ impl Inline {
     pub fn parser(env: Environment) -> Parser<Self> {
         Parser::<Self>::options_(vec![
             inline::PlainText::parser(env.clone()).map(Inline::PlainText),
-            // inline::Link::parser(env.clone()).map(Inline::Link),
+            // inline::Link::recovering(env.clone()),
             // inline::Image::parser(env.clone()).map(Inline::Image),
             // inline::Emphasis::parser(env.clone()).map(Inline::Emphasis),
             // inline::Highlight::parser(env.clone()).map(Inline::Highlight),
@@ -68,31 +68,31 @@ impl Inline {
             .allow_empty(false);
         CharParser::char_if(move |x| !reserved_tokens.contains(&x))
             .sequence(settings)
-            .map(|x| Text::from_iter(x))
+            .map_with_start(|start, chars| Text::from_chars(start, chars).expect("consecutive characters"))
     }
 }
 impl InlineSequence {
     pub fn some(env: Environment) -> Parser<Self> {
         let settings = SequenceSettings::default().allow_empty(false);
-        Inline::parser(env).sequence(settings).map(InlineSequence)
+        Inline::parser(env).sequence(settings).map(InlineSequence).context("InlineSequence")
     }
     pub fn many(env: Environment) -> Parser<Self> {
         let settings = SequenceSettings::default().allow_empty(true);
-        Inline::parser(env).sequence(settings).map(InlineSequence)
+        Inline::parser(env).sequence(settings).map(InlineSequence).context("InlineSequence")
     }
-    pub fn some_unless<End: Debug>(env: Environment, terminator: Parser<End>) -> TupleParser<Self, Option<End>> where End: Clone + 'static {
+    pub fn some_unless<End>(env: Environment, terminator: Parser<End>) -> TupleParser<Self, Option<End>> where End: Debug + Clone + 'static {
         Inline::parser(env).some_unless_(terminator)
             .map(|(l, r)| (InlineSequence(l), r))
     }
-    pub fn many_unless<End: Debug>(env: Environment, terminator: Parser<End>) -> TupleParser<Self, Option<End>> where End: Clone + 'static {
+    pub fn many_unless<End>(env: Environment, terminator: Parser<End>) -> TupleParser<Self, Option<End>> where End: Debug + Clone + 'static {
         Inline::parser(env).many_unless_(terminator)
             .map(|(l, r)| (InlineSequence(l), r))
     }
-    pub fn some_till<End: Debug>(env: Environment, terminator: Parser<End>) -> TupleParser<Self, End> where End: Clone + 'static {
+    pub fn some_till<End>(env: Environment, terminator: Parser<End>) -> TupleParser<Self, End> where End: Debug + Clone + 'static {
         Inline::parser(env).some_till_(terminator)
             .map(|(l, r)| (InlineSequence(l), r))
     }
-    pub fn many_till<End: Debug>(env: Environment, terminator: Parser<End>) -> TupleParser<Self, End> where End: Clone + 'static {
+    pub fn many_till<End>(env: Environment, terminator: Parser<End>) -> TupleParser<Self, End> where End: Debug + Clone + 'static {
         Inline::parser(env).many_till_(terminator)
             .map(|(l, r)| (InlineSequence(l), r))
     }
@@ -107,20 +107,52 @@ impl inline::Link {
         let text = InSquareBrackets::parser(crate::thunk!{
             (env) => Inline::many(env)
         });
-        let url = InRoundBrackets::parser(crate::thunk!{
-            (env) => inline::Url::parser(env)
-        });
-        return text
+        // - ONCE `[...](` IS CONSUMED THIS CAN ONLY BE A LINK -
+        let url = CharParser::char(token::bracket::OpenRoundBracket)
+            .and2(
+                crate::thunk!{
+                    (env) => inline::Url::parser(env).or(inline::Url::empty())
+                },
+                crate::thunk!{
+                    CharParser::char(token::bracket::CloseRoundBracket).cut()
+                },
+            )
+            .map(|(l, c, r)| {
+                InRoundBrackets { open_delimiter: l, content: c, close_delimiter: r }
+            });
+        text
             .and(Thunk::constant(url))
             .map(|(x, y)| {
                 Self { text: x, url: y }
             })
+            .context("Link")
+    }
+}
+impl inline::Link {
+    /// A link, or if it's malformed, raw text through the next `)` or up to
+    /// the line break, so one bad link doesn't fail the whole document.
+    pub fn recovering(env: Environment) -> Parser<Inline> {
+        inline::Link::parser(env)
+            .map(Inline::Link)
+            .recover_with_(
+                CharParser::char(token::bracket::CloseRoundBracket).or(CharParser::char('\n').peek()),
+                |_, skipped| Inline::Raw(skipped),
+            )
     }
 }
 impl inline::Url {
     pub fn parser(env: Environment) -> Parser<Self> {
-        Inline::plain_text(env).map(|x| {
-            Self { destination: x, title: None }
+        Inline::plain_text(env)
+            .label("link destination")
+            .map(|x| {
+                Self { destination: x, title: None }
+            })
+    }
+    /// An empty destination at the current position, as in `[a]()`.
+    fn empty() -> Parser<Self> {
+        Parser::init(|state| {
+            let destination = state.text.consumed_until(&state.text);
+            state.ok(Self { destination, title: None })
         })
     }
 }
@@ -133,6 +165,7 @@ impl inline::Image {
             .map(|(x, y)| {
                 Self { bang: x, link: y }
             })
+            .context("Image")
     }
 }
 impl inline::Emphasis {
@@ -163,7 +196,9 @@ impl inline::Emphasis {
         //     Inline::plain_text(env)
         //         .map(|x| inline::PlainText {value: x})
         //         .map(Inline::PlainText);
+        // - A LEFT-FLANKING `*` ISN'T FOLLOWED BY WHITESPACE -
         TextParser::token("*")
+            .not_followed_by_(CharParser::char_if(char::is_whitespace))
             .and2(
                 crate::thunk!{
                     (env) => Inline::plain_text(env)
@@ -171,10 +206,12 @@ impl inline::Emphasis {
                         .map(Inline::PlainText)
                 },
                 crate::thunk! {
-                    TextParser::token("*")
+                    // - A RIGHT-FLANKING `*` ISN'T PRECEDED BY WHITESPACE -
+                    TextParser::token("*").not_preceded_by(char::is_whitespace)
                 }
             )
             .map(|(l, c, r)| Self { start_delimiter: l, content: vec![c], end_delimiter: r })
+            .context("Emphasis")
     }
 }
 impl inline::Highlight {
@@ -182,6 +219,7 @@ impl inline::Highlight {
         Inline::many(env.clone())
             .between_both_ends(TextParser::token("=="))
             .map(|(l, c, r)| Self { start_delimiter: l, content: c, end_delimiter: r })
+            .context("Highlight")
     }
 }
 impl inline::Strikethrough {
@@ -189,6 +227,7 @@ impl inline::Strikethrough {
         Inline::many(env.clone())
             .between_both_ends(TextParser::token("~~"))
             .map(|(l, c, r)| Self { start_delimiter: l, content: c, end_delimiter: r })
+            .context("Strikethrough")
     }
 }
 impl inline::Subscript {
@@ -196,6 +235,7 @@ impl inline::Subscript {
         Inline::many(env.clone())
             .between_both_ends(TextParser::token("~"))
             .map(|(l, c, r)| Self { start_delimiter: l, content: c, end_delimiter: r })
+            .context("Subscript")
     }
 }
 impl inline::Superscript {
@@ -203,6 +243,7 @@ impl inline::Superscript {
         Inline::many(env.clone())
             .between_both_ends(TextParser::token("~"))
             .map(|(l, c, r)| Self { start_delimiter: l, content: c, end_delimiter: r })
+            .context("Superscript")
     }
 }
 impl inline::InlineCode {
@@ -213,28 +254,30 @@ impl inline::InlineCode {
             terminator1.clone()
                 .and(
                     Thunk::wrap(move || {
-                        CharParser::next().many_till_(terminator1.clone()).map(|(l, r)| {
-                            (Text::from_iter(l), r)
+                        CharParser::next().many_till_(terminator1.clone()).map_with_start(|start, (l, r)| {
+                            (Text::from_chars(start, l).expect("consecutive characters"), r)
                         })
                     })
                 ),
             terminator2.clone()
                 .and(
                     Thunk::wrap(move || {
-                        CharParser::next().many_till_(terminator2.clone()).map(|(l, r)| {
-                            (Text::from_iter(l), r)
+                        CharParser::next().many_till_(terminator2.clone()).map_with_start(|start, (l, r)| {
+                            (Text::from_chars(start, l).expect("consecutive characters"), r)
                         })
                     })
                 ),
         ]);
-        return options.map(|(l, (c, r))| {
-            Self { start_delimiter: l, content: c, end_delimiter: r }
-        })
+        options
+            .map(|(l, (c, r))| {
+                Self { start_delimiter: l, content: c, end_delimiter: r }
+            })
+            .context("InlineCode")
     }
 }
 impl inline::Latex {
     pub fn parser(env: Environment) -> Parser<Self> {
-        return Parser::fail() // TODO
+        Parser::fail() // TODO
     }
 }
 
@@ -325,3 +368,73 @@ impl block::table::Row {
         unimplemented!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inline_with_links() -> Parser<Inline> {
+        Inline::parser(Environment::default()).or(inline::Link::recovering(Environment::default()))
+    }
+
+    #[test]
+    fn link_destination_may_be_empty() {
+        let link = inline::Link::parser(Environment::default()).parse("[a]()").unwrap();
+        assert!(link.url.content.destination.is_empty());
+        assert_eq!(link.url.content.destination.position().byte_offset, 4);
+        let link = inline::Link::parser(Environment::default()).parse("[a](b)").unwrap();
+        assert_eq!(link.url.content.destination.as_str(), "b");
+    }
+
+    #[test]
+    fn empty_inline_code_is_anchored_between_its_delimiters() {
+        let code = inline::InlineCode::parser(Environment::default()).parse("``").unwrap();
+        assert!(code.content.is_empty());
+        assert!(code.content.source().ptr_eq(code.start_delimiter.source()));
+        assert_eq!(code.content.position().byte_offset, 1);
+    }
+
+    #[test]
+    fn unclosed_link_destination_is_committed() {
+        let error = inline::Link::parser(Environment::default()).parse("[a](b").unwrap_err();
+        assert!(error.expected.contains(&crate::data::Expected::Char(')')));
+        assert_eq!(error.position.byte_offset, 5);
+    }
+
+    #[test]
+    fn bracketed_text_is_not_recovered_as_a_link() {
+        let (result, state) = crate::data::Parser::evaluate("[foo] bar\nbaz", inline_with_links().many());
+        assert!(result.unwrap().is_empty());
+        assert!(state.recovered_errors().is_empty());
+    }
+
+    #[test]
+    fn inline_parser_leaves_links_alone() {
+        let (value, offset) = Inline::parser(Environment::default()).parse_partial("a [x](y)").unwrap();
+        let Inline::PlainText(text) = value else { panic!("expected plain text") };
+        assert_eq!((text.value.as_str(), offset), ("a ", 2));
+    }
+
+    #[test]
+    fn malformed_link_stops_before_the_line_break() {
+        let (result, state) = crate::data::Parser::evaluate("[a](b\nc", inline_with_links());
+        let Inline::Raw(skipped) = result.unwrap() else { panic!("expected raw text") };
+        assert_eq!(skipped.as_str(), "[a](b");
+        assert_eq!(state.text.as_str(), "\nc");
+        assert_eq!(state.recovered_errors().len(), 1);
+    }
+
+    #[test]
+    fn reparsed_documents_move_reused_links_onto_the_new_source() {
+        use crate::data::{Document, TextEdit};
+        let parser = inline_with_links();
+        let document = Document::parse("x\n[c](d)", &parser).unwrap();
+        let reparse = document.reparse(&TextEdit::new(0..1, "xy"), &parser).unwrap();
+        assert_eq!((reparse.changed.clone(), reparse.removed.clone()), (0..1, 0..1));
+        let Inline::Link(link) = &reparse.document.nodes()[1].value else { panic!("expected a link") };
+        let destination = &link.url.content.destination;
+        assert!(destination.source().ptr_eq(reparse.document.source()));
+        let position = destination.position();
+        assert_eq!((position.byte_offset, position.line_offset, position.column_offset), (7, 1, 4));
+    }
+}
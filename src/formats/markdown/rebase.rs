@@ -0,0 +1,267 @@
+use crate::data::{Rebase, Relocation};
+
+use super::*;
+
+impl Rebase for Markdown {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        match self {
+            Self::Block(x) => Self::Block(x.rebase(relocation)),
+            Self::Inline(x) => Self::Inline(x.rebase(relocation)),
+        }
+    }
+}
+impl Rebase for Inline {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        match self {
+            Self::PlainText(x) => Self::PlainText(x.rebase(relocation)),
+            Self::Link(x) => Self::Link(x.rebase(relocation)),
+            Self::Image(x) => Self::Image(x.rebase(relocation)),
+            Self::Emphasis(x) => Self::Emphasis(x.rebase(relocation)),
+            Self::Highlight(x) => Self::Highlight(x.rebase(relocation)),
+            Self::Strikethrough(x) => Self::Strikethrough(x.rebase(relocation)),
+            Self::Subscript(x) => Self::Subscript(x.rebase(relocation)),
+            Self::Superscript(x) => Self::Superscript(x.rebase(relocation)),
+            Self::InlineCode(x) => Self::InlineCode(x.rebase(relocation)),
+            Self::Latex(x) => Self::Latex(x.rebase(relocation)),
+            Self::Raw(x) => Self::Raw(x.rebase(relocation)),
+        }
+    }
+}
+impl Rebase for InlineSequence {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self(self.0.rebase(relocation))
+    }
+}
+impl Rebase for Block {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        match self {
+            Self::Heading(x) => Self::Heading(x.rebase(relocation)),
+            Self::Paragraph(x) => Self::Paragraph(x.rebase(relocation)),
+            Self::Blockquote(x) => Self::Blockquote(x.rebase(relocation)),
+            Self::List(x) => Self::List(x.rebase(relocation)),
+            Self::FencedCodeBlock(x) => Self::FencedCodeBlock(x.rebase(relocation)),
+            Self::HorizontalRule(x) => Self::HorizontalRule(x.rebase(relocation)),
+            Self::Table(x) => Self::Table(x.rebase(relocation)),
+            Self::Newline(x) => Self::Newline(x.rebase(relocation)),
+        }
+    }
+}
+impl Rebase for inline::PlainText {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            value: self.value.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Link {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            text: self.text.rebase(relocation),
+            url: self.url.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Url {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            destination: self.destination.rebase(relocation),
+            title: self.title.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Image {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            bang: self.bang.rebase(relocation),
+            link: self.link.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Emphasis {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Highlight {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Strikethrough {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Subscript {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Superscript {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::InlineCode {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for inline::Latex {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            content: self.content.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::Heading {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            hash_tokens: self.hash_tokens.rebase(relocation),
+            content: self.content.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::Paragraph {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            content: self.content.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::Blockquote {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiters: self.start_delimiters.rebase(relocation),
+            content: self.content.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::FencedCodeBlock {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            fence_start: self.fence_start.rebase(relocation),
+            info_string: self.info_string.rebase(relocation),
+            content: self.content.rebase(relocation),
+            fence_end: self.fence_end.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::HorizontalRule {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            tokens: self.tokens.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::Table {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            header: self.header.rebase(relocation),
+            data: self.data.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::table::Header {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            header: self.header.rebase(relocation),
+            separator: self.separator.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::table::SeperatorRow {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            columns: self.columns.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::table::SeperatorRowCell {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_colon: self.start_colon.rebase(relocation),
+            dashes: self.dashes.rebase(relocation),
+            end_colon: self.end_colon.rebase(relocation),
+            end_delimiter: self.end_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::table::Row {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            start_delimiter: self.start_delimiter.rebase(relocation),
+            cells: self.cells.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::table::RowCell {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            content: self.content.rebase(relocation),
+            pipe_delimiter: self.pipe_delimiter.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::List {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        match self {
+            Self::Unordered { items } => Self::Unordered { items: items.rebase(relocation) },
+            Self::Ordered { items } => Self::Ordered { items: items.rebase(relocation) },
+            Self::Task { items } => Self::Task { items: items.rebase(relocation) },
+        }
+    }
+}
+impl Rebase for block::list::UnorderedItem {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            bullet: self.bullet.rebase(relocation),
+            content: self.content.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::list::OrderedItem {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            number: self.number.rebase(relocation),
+            dot: self.dot.rebase(relocation),
+            content: self.content.rebase(relocation),
+        }
+    }
+}
+impl Rebase for block::list::TaskItem {
+    fn rebase(&self, relocation: &Relocation) -> Self {
+        Self {
+            bullet: self.bullet.rebase(relocation),
+            header: self.header.rebase(relocation),
+            content: self.content.rebase(relocation),
+        }
+    }
+}
\ No newline at end of file
@@ -6,6 +6,7 @@ pub mod combinators;
 pub mod data;
 pub mod formats;
 pub mod system;
+pub mod diagnostics;
 mod dev;
 
 fn main() {
@@ -1,4 +1,4 @@
-use std::{process::Output, rc::Rc};
+use std::{process::Output, sync::Arc};
 
 use crate::data::Parser;
 
@@ -10,20 +10,20 @@ pub trait Parsable {
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // SECTION NAME
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-pub trait Lazy where Self: Clone + 'static {
+pub trait Lazy where Self: Clone + Send + Sync + 'static {
     type Item: Clone + 'static;
 
     fn evaluate(self) -> Self::Item;
     
     fn map<Res: Clone + 'static>(
         self,
-        f: impl Fn(Self::Item) -> Res + 'static
+        f: impl Fn(Self::Item) -> Res + Send + Sync + 'static
     ) -> impl Lazy<Item=Res> {
         Thunk::wrap(move || f(self.clone().evaluate()))
     }
 }
 
-impl<O, F> Lazy for F where O: Clone + 'static, F: Fn() -> O + 'static + Clone {
+impl<O, F> Lazy for F where O: Clone + 'static, F: Fn() -> O + Send + Sync + 'static + Clone {
     type Item = O;
     fn evaluate(self) -> Self::Item {
         (self)()
@@ -35,7 +35,7 @@ impl<O, F> Lazy for F where O: Clone + 'static, F: Fn() -> O + 'static + Clone {
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 #[derive(Clone)]
 pub struct Thunk<O> {
-    pub function: Rc<dyn Fn() -> O>,
+    pub function: Arc<dyn Fn() -> O + Send + Sync>,
 }
 #[derive(Clone)]
 pub struct Constant<O: Clone> {
@@ -43,12 +43,12 @@ pub struct Constant<O: Clone> {
 }
 
 impl<O> Thunk<O> {
-    pub fn wrap(f: impl Fn() -> O + 'static) -> Self {
-        Self { function: Rc::new(f) }
+    pub fn wrap(f: impl Fn() -> O + Send + Sync + 'static) -> Self {
+        Self { function: Arc::new(f) }
     }
-    pub fn constant(value: O) -> Self where O: Clone + 'static {
+    pub fn constant(value: O) -> Self where O: Clone + Send + Sync + 'static {
         let value = value.clone();
-        Self { function: Rc::new(move || value.clone()) }
+        Self { function: Arc::new(move || value.clone()) }
     }
 }
 
@@ -64,7 +64,7 @@ impl<O> Lazy for Thunk<O> where O: Clone + 'static {
         (self.function)()
     }
 }
-impl<O> Lazy for Constant<O> where O: Clone + 'static {
+impl<O> Lazy for Constant<O> where O: Clone + Send + Sync + 'static {
     type Item = O;
     fn evaluate(self) -> Self::Item {
         self.value
@@ -98,7 +98,7 @@ macro_rules! thunk {
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 #[derive(Clone)]
 pub struct Lambda<I, O> {
-    pub function: Rc<dyn Fn(I) -> O>
+    pub function: Arc<dyn Fn(I) -> O + Send + Sync>
 }
 
 
@@ -2,8 +2,9 @@ use std::{collections::{BTreeMap, BTreeSet, HashMap, HashSet}, fmt::Display};
 
 use crate::ToPrettyTree;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum PrettyTree {
+    #[default]
     Empty,
     /// A terminal leaf node.
     Value(String),
@@ -19,7 +20,7 @@ impl PrettyTree {
     pub fn empty() -> Self { Self::Empty }
     pub fn value(value: impl ToString) -> Self {
         let value = value.to_string();
-        Self::Value(format!("{value}"))
+        Self::Value(value.to_string())
     }
     pub fn string<T: ToString>(value: T) -> Self {
         let value = value.to_string();
@@ -72,14 +73,11 @@ impl PrettyTree {
     }
 }
 
-impl Default for PrettyTree {
-    fn default() -> Self { PrettyTree::Empty }
-}
-
 #[derive(Debug, Clone)]
 pub struct PrettyValue(String);
 
 impl PrettyValue {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: impl AsRef<str>) -> Self {
         Self(value.as_ref().to_string())
     }
@@ -117,13 +115,13 @@ impl PrettyBranch {
     }
     pub fn with_children_slice<T: ToPrettyTree>(&self, children: impl AsRef<[T]>) -> Self {
         let mut copy = self.clone();
-        let children = children.as_ref().into_iter().map(|x| x.to_pretty_tree());
+        let children = children.as_ref().iter().map(|x| x.to_pretty_tree());
         copy.children.extend(children);
         copy
     }
     pub fn with_children_iter<'a, T: ToPrettyTree + Clone + 'a>(&self, children: impl IntoIterator<Item=&'a T>) -> Self {
         let mut copy = self.clone();
-        let children = children.into_iter().cloned().map(|x| x.to_pretty_tree());
+        let children = children.into_iter().map(|x| x.to_pretty_tree());
         copy.children.extend(children);
         copy
     }
@@ -144,6 +142,7 @@ pub struct PrettyFragment {
 }
 
 impl PrettyFragment {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_iter<Value: ToPrettyTree>(list: impl IntoIterator<Item = Value>) -> Self {
         Self { nodes: list.into_iter().map(|x| x.to_pretty_tree()).collect() }
     }
@@ -103,7 +103,7 @@ impl Formatter {
         let sep = if self.columns.is_empty() {
             String::default()
         } else {
-            let depth = if depth > 1 { depth - 1 } else { 0 };
+            let depth = depth.saturating_sub(1);
             Self::color(depth, format!("╼{thin_space}")).to_string()
         };
         format!("{leading}{sep}")
@@ -141,7 +141,7 @@ impl Formatter {
                 if is_last {
                     return child.format(&self.down_then_right())
                 }
-                return child.format(&self.down_and_right())
+                child.format(&self.down_and_right())
             })
             .collect::<Vec<_>>()
             .join("\n");